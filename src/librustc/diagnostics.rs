@@ -2107,4 +2107,5 @@ register_diagnostics! {
     E0495, // cannot infer an appropriate lifetime due to conflicting requirements
     E0498, // malformed plugin attribute
     E0514, // metadata version mismatch
+    E0517, // multiple applicable implementations found
 }
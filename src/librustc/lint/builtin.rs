@@ -111,6 +111,12 @@ declare_lint! {
     Allow,
     "detects trivial casts of numeric types which could be removed"
 }
+declare_lint! {
+    pub RFC_1214,
+    Warn,
+    "detects code that RFC 1214 makes ill-formed, still accepted with a warning"
+}
+
 /// Does nothing as a lint pass, but registers some `Lint`s
 /// which are used by other parts of the compiler.
 #[derive(Copy, Clone)]
@@ -134,7 +140,8 @@ impl LintPass for HardwiredLints {
             VARIANT_SIZE_DIFFERENCES,
             FAT_PTR_TRANSMUTES,
             TRIVIAL_CASTS,
-            TRIVIAL_NUMERIC_CASTS
+            TRIVIAL_NUMERIC_CASTS,
+            RFC_1214
         )
     }
 }
@@ -96,7 +96,7 @@ enum FindLintError {
 }
 
 impl LintStore {
-    fn get_level_source(&self, lint: LintId) -> LevelSource {
+    pub fn get_level_source(&self, lint: LintId) -> LevelSource {
         match self.levels.get(&lint) {
             Some(&s) => s,
             None => (Allow, Default),
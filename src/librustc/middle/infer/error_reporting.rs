@@ -82,6 +82,7 @@ use middle::def_id::DefId;
 use middle::infer;
 use middle::region;
 use middle::subst;
+use middle::traits;
 use middle::ty::{self, Ty, HasTypeFlags};
 use middle::ty::{Region, ReFree};
 use middle::ty::error::TypeError;
@@ -767,6 +768,18 @@ impl<'a, 'tcx> ErrorReporting<'tcx> for InferCtxt<'a, 'tcx> {
                                         "type must outlive ",
                                         sub,
                                         "");
+                let mut path = vec![];
+                if let Some(ref_ty) = field_path_to_region(self.tcx, ty, sub, sup, &mut path) {
+                    if !path.is_empty() {
+                        traits::emit_note(
+                            self.tcx.sess,
+                            span,
+                            &format!("the field `{}` has type `{}`, which does not \
+                                      live long enough",
+                                     path.join("."),
+                                     self.ty_to_string(ref_ty)));
+                    }
+                }
             }
             infer::RelateRegionParamBound(span) => {
                 span_err!(self.tcx.sess, span, E0478,
@@ -1906,6 +1919,57 @@ fn lifetimes_in_scope(tcx: &ty::ctxt,
     return taken;
 }
 
+/// Walks the fields of `ty`, outermost first, looking for the reference
+/// that keeps it from outliving `required`: any non-`'static` reference
+/// if `required` is `'static`, else one whose region is `actual`. On
+/// success `path` holds the field names leading to it.
+fn field_path_to_region<'tcx>(tcx: &ty::ctxt<'tcx>,
+                              ty: Ty<'tcx>,
+                              required: Region,
+                              actual: Region,
+                              path: &mut Vec<String>)
+                              -> Option<Ty<'tcx>> {
+    // Recursive types (through a `Box`, say) are not walked into, but
+    // bound the depth anyway.
+    if path.len() > 8 {
+        return None;
+    }
+
+    let fields: Vec<(String, Ty<'tcx>)> = match ty.sty {
+        ty::TyRef(region, _) => {
+            let fails = if required == ty::ReStatic {
+                *region != ty::ReStatic
+            } else {
+                *region == actual
+            };
+            return if fails { Some(ty) } else { None };
+        }
+        ty::TyStruct(def, substs) => {
+            def.struct_variant().fields.iter().enumerate().map(|(i, field)| {
+                let name = if field.name == token::special_idents::unnamed_field.name {
+                    i.to_string()
+                } else {
+                    field.name.to_string()
+                };
+                (name, field.ty(tcx, substs))
+            }).collect()
+        }
+        ty::TyTuple(ref tys) => {
+            tys.iter().enumerate().map(|(i, &ty)| (i.to_string(), ty)).collect()
+        }
+        _ => return None,
+    };
+
+    for (name, field_ty) in fields {
+        path.push(name);
+        if let Some(ref_ty) = field_path_to_region(tcx, field_ty, required, actual, path) {
+            return Some(ref_ty);
+        }
+        path.pop();
+    }
+    None
+}
+
 // LifeGiver is responsible for generating fresh lifetime names
 struct LifeGiver {
     taken: HashSet<String>,
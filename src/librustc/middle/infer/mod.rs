@@ -37,7 +37,7 @@ use middle::ty::error::{ExpectedFound, TypeError, UnconstrainedNumeric};
 use middle::ty::fold::{TypeFolder, TypeFoldable};
 use middle::ty::relate::{Relate, RelateResult, TypeRelation};
 use rustc_data_structures::unify::{self, UnificationTable};
-use std::cell::{RefCell, Ref};
+use std::cell::{Cell, RefCell, Ref};
 use std::fmt;
 use std::rc::Rc;
 use syntax::ast;
@@ -96,6 +96,22 @@ pub struct InferCtxt<'a, 'tcx: 'a> {
     // avoid reporting the same error twice.
     pub reported_trait_errors: RefCell<FnvHashSet<traits::TraitErrorKey<'tcx>>>,
 
+    // the erased predicates of the RFC 1214 warnings reported so far; the
+    // RFC 1214 explanation is only noted on the first warning for each,
+    // and suppressed while `suppress_rfc1214_note` is set.
+    pub rfc1214_noted_predicates: RefCell<FnvHashSet<ty::Predicate<'tcx>>>,
+    pub suppress_rfc1214_note: Cell<bool>,
+
+    // the traits reported as not object-safe so far, with the span of
+    // each report; selection errors for their object types at the same
+    // span follow from the report and are not reported again.
+    pub reported_object_safety_errors: RefCell<FnvHashSet<(DefId, Span)>>,
+
+    // the bound region and the region it was related to in the most recent
+    // failed leak check, so that error reporting can show which
+    // instantiation of a higher-ranked bound was rejected.
+    pub last_leak_check_failure: Cell<Option<(ty::BoundRegion, ty::Region)>>,
+
     // This is a temporary field used for toggling on normalization in the inference context,
     // as we move towards the approach described here:
     // https://internals.rust-lang.org/t/flattening-the-contexts-for-fun-and-profit/2293
@@ -379,6 +395,10 @@ pub fn new_infer_ctxt<'a, 'tcx>(tcx: &'a ty::ctxt<'tcx>,
         parameter_environment: param_env.unwrap_or(tcx.empty_parameter_environment()),
         fulfillment_cx: RefCell::new(traits::FulfillmentContext::new(errors_will_be_reported)),
         reported_trait_errors: RefCell::new(FnvHashSet()),
+        rfc1214_noted_predicates: RefCell::new(FnvHashSet()),
+        suppress_rfc1214_note: Cell::new(false),
+        reported_object_safety_errors: RefCell::new(FnvHashSet()),
+        last_leak_check_failure: Cell::new(None),
         normalize: false,
         err_count_on_creation: tcx.sess.err_count()
     }
@@ -912,7 +932,10 @@ impl<'a, 'tcx> InferCtxt<'a, 'tcx> {
 
         match higher_ranked::leak_check(self, skol_map, snapshot) {
             Ok(()) => Ok(()),
-            Err((br, r)) => Err(TypeError::RegionsInsufficientlyPolymorphic(br, r))
+            Err((br, r)) => {
+                self.last_leak_check_failure.set(Some((br, r)));
+                Err(TypeError::RegionsInsufficientlyPolymorphic(br, r))
+            }
         }
     }
 
@@ -126,6 +126,14 @@ pub fn trait_ref_is_knowable<'tcx>(tcx: &ty::ctxt<'tcx>, trait_ref: &ty::TraitRe
     orphan_check_trait_ref(tcx, trait_ref, InferIsLocal(true)).is_err()
 }
 
+/// True if the orphan rules allow the local crate to write an impl for
+/// `trait_ref`; if not, only the crates defining the trait or one of the
+/// types involved can.
+pub fn local_impl_allowed<'tcx>(tcx: &ty::ctxt<'tcx>, trait_ref: &ty::TraitRef<'tcx>) -> bool
+{
+    orphan_check_trait_ref(tcx, trait_ref, InferIsLocal(false)).is_ok()
+}
+
 type SubstsFn = for<'a,'tcx> fn(infcx: &InferCtxt<'a, 'tcx>,
                                 span: Span,
                                 impl_def_id: DefId)
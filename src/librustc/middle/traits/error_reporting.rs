@@ -13,26 +13,150 @@ use super::{
     FulfillmentErrorCode,
     MismatchedProjectionTypes,
     Obligation,
+    ObligationCause,
     ObligationCauseCode,
     OutputTypeParameterMismatch,
     TraitNotObjectSafe,
+    Overflow,
     PredicateObligation,
+    TraitObligation,
+    SelectionContext,
     SelectionError,
     ObjectSafetyViolation,
     MethodViolationCode,
     object_safety_violations,
 };
+use super::coherence;
+use super::project;
+use super::util::{predicate_for_trait_ref, supertrait_def_ids, supertraits,
+                  trait_ref_for_builtin_bound};
 
 use fmt_macros::{Parser, Piece, Position};
+use front::map as ast_map;
+use lint::{self, builtin};
+use middle::def;
 use middle::def_id::DefId;
 use middle::infer::InferCtxt;
+use middle::subst::{self, Subst};
 use middle::ty::{self, ToPredicate, HasTypeFlags, ToPolyTraitRef, TraitRef, Ty};
-use middle::ty::fold::TypeFoldable;
+use middle::ty::error::{TypeError, UnconstrainedNumeric};
+use middle::ty::fold::{BottomUpFolder, TypeFoldable};
+use session::Session;
+use util::lev_distance::closest_name;
 use util::nodemap::{FnvHashMap, FnvHashSet};
 
+use std::borrow::Cow;
 use std::fmt;
-use syntax::codemap::Span;
+use std::mem;
+use std::usize;
+use syntax::ast;
+use syntax::codemap::{BytePos, CodeMap, Span, DUMMY_SP};
 use syntax::attr::{AttributeMethods, AttrMetaMethods};
+use syntax::diagnostic::{Error, Help, Level, Note, Warning};
+use rustc_front::hir;
+use rustc_front::print::pprust;
+use rustc_front::util as hir_util;
+use rustc_front::visit::{self, Visitor};
+
+/// Like `span_err_or_warn!`, but builds a `TraitErrorReport` with no
+/// notes instead of emitting the diagnostic.
+macro_rules! trait_error_report {
+    ($is_warning:expr, $span:expr, $code:ident, $($message:tt)*) => ({
+        __diagnostic_used!($code);
+        TraitErrorReport {
+            is_warning: $is_warning,
+            code: stringify!($code),
+            span: $span,
+            message: format!($($message)*),
+            children: vec![],
+            attribute_errors: vec![],
+        }
+    })
+}
+
+/// A trait error and the notes and helps attached to it, in the order
+/// they are emitted. Building one leaves the session untouched, so a
+/// report can be inspected, or rendered some other way, before `emit`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraitErrorReport {
+    pub is_warning: bool,
+    pub code: &'static str,
+    pub span: Span,
+    pub message: String,
+    pub children: Vec<TraitErrorChild>,
+    /// Errors in the `#[rustc_on_unimplemented]` attributes consulted
+    /// while building the report. They are emitted ahead of it.
+    pub attribute_errors: Vec<TraitErrorReport>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TraitErrorChild {
+    /// A note that only names the line of its span.
+    Note(Span, String),
+    /// A note that also shows the source at its span.
+    SpanNote(Span, String),
+    Help(Span, String),
+    /// A help that proposes replacing the source at its span with the
+    /// given text.
+    Suggestion(Span, String, String),
+    /// The note that an RFC 1214 warning will become an error.
+    Rfc1214(Span),
+}
+
+impl TraitErrorReport {
+    /// Emits the report into the session, honouring
+    /// `-Z gcc-style-trait-errors` like the other trait errors.
+    pub fn emit(&self, sess: &Session) {
+        for error in &self.attribute_errors {
+            error.emit(sess);
+        }
+        let mut names = StableNames::new(sess);
+        emit_err_or_warn(sess, self.is_warning, self.span, self.code,
+                         &names.apply(&self.message));
+        for child in &self.children {
+            match *child {
+                TraitErrorChild::Note(span, ref msg) => {
+                    emit_note(sess, span, &names.apply(msg))
+                }
+                TraitErrorChild::SpanNote(span, ref msg) => {
+                    emit_span_note(sess, span, &names.apply(msg))
+                }
+                TraitErrorChild::Help(span, ref msg) => {
+                    emit_help(sess, span, &names.apply(msg))
+                }
+                TraitErrorChild::Suggestion(span, ref msg, ref suggestion) => {
+                    emit_suggestion(sess, span, &names.apply(msg), suggestion.clone())
+                }
+                TraitErrorChild::Rfc1214(span) => {
+                    // A denied RFC1214 warning is already a hard error.
+                    if self.is_warning {
+                        note_rfc_1214(sess, span)
+                    }
+                }
+            }
+        }
+    }
+
+    fn note(&mut self, span: Span, msg: &str) {
+        self.children.push(TraitErrorChild::Note(span, msg.to_string()));
+    }
+
+    fn span_note(&mut self, span: Span, msg: &str) {
+        self.children.push(TraitErrorChild::SpanNote(span, msg.to_string()));
+    }
+
+    fn help(&mut self, span: Span, msg: &str) {
+        self.children.push(TraitErrorChild::Help(span, msg.to_string()));
+    }
+
+    fn suggestion(&mut self, span: Span, msg: &str, suggestion: String) {
+        self.children.push(TraitErrorChild::Suggestion(span, msg.to_string(), suggestion));
+    }
+
+    fn rfc_1214(&mut self, span: Span) {
+        self.children.push(TraitErrorChild::Rfc1214(span));
+    }
+}
 
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub struct TraitErrorKey<'tcx> {
@@ -47,309 +171,2810 @@ impl<'tcx> TraitErrorKey<'tcx> {
         let predicate =
             infcx.resolve_type_vars_if_possible(&e.obligation.predicate);
         TraitErrorKey {
-            is_warning: is_warning(&e.obligation),
+            is_warning: is_warning(infcx.tcx.sess, &e.obligation),
             span: e.obligation.cause.span,
             predicate: infcx.tcx.erase_regions(&predicate)
         }
     }
 }
 
+/// The number of other locations shown when several fulfillment errors
+/// for the same predicate are collapsed into one.
+const MAX_COLLAPSED_SPANS: usize = 3;
+
 pub fn report_fulfillment_errors<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
                                            errors: &Vec<FulfillmentError<'tcx>>) {
-    for error in errors {
-        report_fulfillment_error(infcx, error);
+    let errors = &in_source_order(infcx, errors);
+    let demoted = demoted_projection_errors(infcx, errors);
+    let is_demoted = |index: usize| {
+        demoted.values().any(|projections| projections.contains(&index))
+    };
+
+    // Under `-Z collapse-trait-errors`, errors for the same predicate at
+    // different spans are reported once, with a summary of the other
+    // spans; warnings and errors are kept apart.
+    let collapse = infcx.tcx.sess.opts.debugging_opts.collapse_trait_errors;
+    let mut groups: FnvHashMap<(bool, ty::Predicate<'tcx>), Vec<usize>> = FnvHashMap();
+    for index in (0..errors.len()).filter(|&index| collapse && !is_demoted(index)) {
+        let key = TraitErrorKey::from_error(infcx, &errors[index]);
+        groups.entry((key.is_warning, key.predicate)).or_insert(vec![]).push(index);
+    }
+    let mut collapsed = FnvHashSet();
+
+    // Errors derived from the same root obligation are causally linked:
+    // fixing the root usually fixes all of them, so under
+    // `-Z related-trait-errors` each one points at the others.
+    let relate = infcx.tcx.sess.opts.debugging_opts.related_trait_errors;
+    let mut related: FnvHashMap<(bool, ty::PolyTraitRef<'tcx>), Vec<usize>> = FnvHashMap();
+    for index in (0..errors.len()).filter(|&index| relate && !is_demoted(index)) {
+        if let Some(root) = root_trait_ref(infcx, &errors[index]) {
+            let is_warning = is_warning(infcx.tcx.sess, &errors[index].obligation);
+            related.entry((is_warning, root)).or_insert(vec![]).push(index);
+        }
+    }
+    let mut reported = vec![false; errors.len()];
+
+    // Several traits missing on the same type at the same place are
+    // reported as one error listing all of them.
+    let bound_groups = missing_bound_groups(infcx, errors, &demoted);
+    let mut grouped = FnvHashSet();
+
+    for (index, error) in errors.iter().enumerate() {
+        if is_demoted(index) {
+            // Reported as a note on the selection error it stems from.
+            infcx.reported_trait_errors.borrow_mut()
+                 .insert(TraitErrorKey::from_error(infcx, error));
+            continue;
+        }
+        if collapsed.contains(&index) || grouped.contains(&index) {
+            continue;
+        }
+        if let Some(group) = bound_groups.get(&index) {
+            let members = unreported_errors(infcx, errors, group);
+            if members.len() > 1 {
+                report_missing_bounds(infcx, errors, &members);
+                for &member in &members {
+                    reported[member] = true;
+                    grouped.insert(member);
+                }
+                continue;
+            }
+        }
+        if let Some(mut report) = fulfillment_error_report(infcx, error) {
+            reported[index] = true;
+            if let Some(projections) = demoted.get(&index) {
+                for &projection_index in projections {
+                    let projection = &errors[projection_index].obligation;
+                    report.note(
+                        projection.cause.span,
+                        &format!("this also causes a type mismatch resolving `{}` (E0271)",
+                                 infcx.resolve_type_vars_if_possible(&projection.predicate)));
+                }
+            }
+            let key = TraitErrorKey::from_error(infcx, error);
+            if let Some(group) = groups.get(&(key.is_warning, key.predicate)) {
+                if group[0] == index {
+                    note_collapsed_errors(infcx, errors, error, &group[1..], &mut collapsed,
+                                          &mut report);
+                }
+            }
+            if let Some(root) = root_trait_ref(infcx, error) {
+                if let Some(group) = related.get(&(key.is_warning, root)) {
+                    note_related_errors(infcx, errors, index, group, &reported, &collapsed,
+                                        &mut report);
+                }
+            }
+            report.emit(infcx.tcx.sess);
+        }
+    }
+}
+
+/// `errors` sorted by span, with dummy spans last. Errors at the same
+/// span are ordered by error code and then by predicate, so that the
+/// output does not depend on the order in which they were found.
+pub fn in_source_order<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                 errors: &[FulfillmentError<'tcx>])
+                                 -> Vec<FulfillmentError<'tcx>>
+{
+    let mut keyed: Vec<_> = errors.iter().map(|error| {
+        let span = error.obligation.cause.span;
+        let predicate = infcx.resolve_type_vars_if_possible(&error.obligation.predicate);
+        let key = (span == DUMMY_SP, span.lo.0, span.hi.0,
+                   fulfillment_error_code(error), format!("{:?}", predicate));
+        (key, error.clone())
+    }).collect();
+    keyed.sort_by(|a, b| a.0.cmp(&b.0));
+    keyed.into_iter().map(|(_, error)| error).collect()
+}
+
+/// The code of the diagnostic that `error` is (usually) reported as.
+fn fulfillment_error_code(error: &FulfillmentError) -> &'static str {
+    match error.code {
+        FulfillmentErrorCode::CodeSelectionError(SelectionError::Unimplemented) => {
+            if let ObligationCauseCode::CompareImplMethodObligation(..) =
+                    error.obligation.cause.code {
+                return "E0276";
+            }
+            match error.obligation.predicate {
+                ty::Predicate::Trait(..) => "E0277",
+                ty::Predicate::Equate(..) => "E0278",
+                ty::Predicate::RegionOutlives(..) => "E0279",
+                ty::Predicate::ObjectSafe(..) => "E0038",
+                ty::Predicate::Projection(..) |
+                ty::Predicate::TypeOutlives(..) |
+                ty::Predicate::WellFormed(..) => "E0280",
+            }
+        }
+        FulfillmentErrorCode::CodeSelectionError(OutputTypeParameterMismatch(..)) => "E0281",
+        FulfillmentErrorCode::CodeSelectionError(TraitNotObjectSafe(..)) => "E0038",
+        FulfillmentErrorCode::CodeSelectionError(Overflow) => "E0275",
+        FulfillmentErrorCode::CodeProjectionError(..) => "E0271",
+        FulfillmentErrorCode::CodeAmbiguity => "E0282",
+    }
+}
+
+/// The outermost trait reference that `error`'s obligation was derived
+/// from, if it was derived at all.
+fn root_trait_ref<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                            error: &FulfillmentError<'tcx>)
+                            -> Option<ty::PolyTraitRef<'tcx>>
+{
+    derived_trait_refs(&error.obligation.cause.code).pop().map(|root| {
+        infcx.tcx.erase_regions(&infcx.resolve_type_vars_if_possible(&root))
+    })
+}
+
+/// Points from the error at `index` to the other errors of its group
+/// that are (or are about to be) reported at another location.
+fn note_related_errors<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                 errors: &[FulfillmentError<'tcx>],
+                                 index: usize,
+                                 group: &[usize],
+                                 reported: &[bool],
+                                 collapsed: &FnvHashSet<usize>,
+                                 report: &mut TraitErrorReport)
+{
+    let span = errors[index].obligation.cause.span;
+    let mut spans: Vec<Span> = vec![];
+    for &other in group {
+        if other == index || collapsed.contains(&other) {
+            continue;
+        }
+        // Earlier errors must actually have been reported; later ones
+        // will be, unless an identical error already was.
+        let will_be_reported = if other < index {
+            reported[other]
+        } else {
+            let key = TraitErrorKey::from_error(infcx, &errors[other]);
+            !infcx.reported_trait_errors.borrow().contains(&key)
+        };
+        let other_span = errors[other].obligation.cause.span;
+        if will_be_reported && other_span != span && !spans.contains(&other_span) {
+            spans.push(other_span);
+        }
+    }
+
+    let codemap = infcx.tcx.sess.codemap();
+    for other_span in spans {
+        report.note(span,
+                    &format!("see the related error at {}", codemap.span_to_string(other_span)));
+    }
+}
+
+/// Marks the other errors of `error`'s group as reported, and notes how
+/// many distinct locations they were found at.
+fn note_collapsed_errors<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                   errors: &[FulfillmentError<'tcx>],
+                                   error: &FulfillmentError<'tcx>,
+                                   others: &[usize],
+                                   collapsed: &mut FnvHashSet<usize>,
+                                   report: &mut TraitErrorReport)
+{
+    let mut spans: Vec<Span> = vec![];
+    for &index in others {
+        let key = TraitErrorKey::from_error(infcx, &errors[index]);
+        let span = key.span;
+        if !infcx.reported_trait_errors.borrow_mut().insert(key) {
+            continue;
+        }
+        collapsed.insert(index);
+        if span != error.obligation.cause.span && !spans.contains(&span) {
+            spans.push(span);
+        }
+    }
+    if spans.is_empty() {
+        return;
+    }
+
+    let predicate = infcx.resolve_type_vars_if_possible(&error.obligation.predicate);
+    report.note(
+        error.obligation.cause.span,
+        &format!("`{}` is also required at {} other location{}",
+                 predicate,
+                 spans.len(),
+                 if spans.len() == 1 { "" } else { "s" }));
+    for &span in spans.iter().take(MAX_COLLAPSED_SPANS) {
+        report.span_note(span, "also required here");
+    }
+}
+
+/// A projection error (E0271) and a selection error (E0277) at the same
+/// span often have the same root: the trait whose associated type is
+/// being projected is not implemented at all. Maps the index of each
+/// such selection error to the indices of the projection errors that
+/// should be demoted to notes under it.
+fn demoted_projection_errors<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                       errors: &[FulfillmentError<'tcx>])
+                                       -> FnvHashMap<usize, Vec<usize>>
+{
+    let tcx = infcx.tcx;
+    let erased_chain = |trait_ref: ty::PolyTraitRef<'tcx>, code: &ObligationCauseCode<'tcx>| {
+        let mut chain = vec![trait_ref];
+        chain.extend(derived_trait_refs(code));
+        chain.into_iter()
+             .map(|r| tcx.erase_regions(&infcx.resolve_type_vars_if_possible(&r)))
+             .collect::<Vec<_>>()
+    };
+
+    let mut demoted: FnvHashMap<usize, Vec<usize>> = FnvHashMap();
+    for (projection_index, projection_error) in errors.iter().enumerate() {
+        let projection_chain = match (&projection_error.code,
+                                      &projection_error.obligation.predicate) {
+            (&FulfillmentErrorCode::CodeProjectionError(_),
+             &ty::Predicate::Projection(ref data)) => {
+                erased_chain(data.to_poly_trait_ref(), &projection_error.obligation.cause.code)
+            }
+            _ => continue,
+        };
+        let root = errors.iter().position(|selection_error| {
+            match (&selection_error.code, &selection_error.obligation.predicate) {
+                (&FulfillmentErrorCode::CodeSelectionError(SelectionError::Unimplemented),
+                 &ty::Predicate::Trait(ref data)) => {
+                    selection_error.obligation.cause.span ==
+                        projection_error.obligation.cause.span &&
+                    is_warning(tcx.sess, &selection_error.obligation) ==
+                        is_warning(tcx.sess, &projection_error.obligation) &&
+                    erased_chain(data.to_poly_trait_ref(), &selection_error.obligation.cause.code)
+                        .iter().any(|r| projection_chain.contains(r))
+                }
+                _ => false,
+            }
+        });
+        if let Some(selection_index) = root {
+            demoted.entry(selection_index).or_insert(vec![]).push(projection_index);
+        }
+    }
+    demoted
+}
+
+/// Groups the `Unimplemented` trait errors that share a self type, a
+/// span, a cause and whether they are warnings. Each group of two or
+/// more is keyed by the index of its first error.
+fn missing_bound_groups<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                  errors: &[FulfillmentError<'tcx>],
+                                  demoted: &FnvHashMap<usize, Vec<usize>>)
+                                  -> FnvHashMap<usize, Vec<usize>>
+{
+    let tcx = infcx.tcx;
+    let mut groups: Vec<(Ty<'tcx>, Vec<usize>)> = vec![];
+    for (index, error) in errors.iter().enumerate() {
+        // Errors with projection errors demoted under them keep their
+        // own report, as do E0276 and errors already tainted by others.
+        if demoted.contains_key(&index) {
+            continue;
+        }
+        let self_ty = match (&error.code, &error.obligation.predicate) {
+            (&FulfillmentErrorCode::CodeSelectionError(SelectionError::Unimplemented),
+             &ty::Predicate::Trait(ref data)) => {
+                let trait_ref = infcx.resolve_type_vars_if_possible(&data.to_poly_trait_ref());
+                if trait_ref.references_error() {
+                    continue;
+                }
+                tcx.erase_regions(&trait_ref.self_ty())
+            }
+            _ => continue,
+        };
+        if let ObligationCauseCode::CompareImplMethodObligation(..) =
+                *unwrapped_cause_code(&error.obligation.cause.code) {
+            continue;
+        }
+
+        let obligation = &error.obligation;
+        let group = groups.iter().position(|&(group_self_ty, ref members)| {
+            let other = &errors[members[0]].obligation;
+            group_self_ty == self_ty &&
+            other.cause.span == obligation.cause.span &&
+            other.cause.code == obligation.cause.code &&
+            is_warning(tcx.sess, other) == is_warning(tcx.sess, obligation)
+        });
+        match group {
+            Some(group) => groups[group].1.push(index),
+            None => groups.push((self_ty, vec![index])),
+        }
+    }
+
+    groups.into_iter()
+          .filter(|&(_, ref members)| members.len() > 1)
+          .map(|(_, members)| (members[0], members))
+          .collect()
+}
+
+/// The errors of `group` that have not been reported yet, without
+/// duplicates.
+fn unreported_errors<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                               errors: &[FulfillmentError<'tcx>],
+                               group: &[usize])
+                               -> Vec<usize>
+{
+    let reported_trait_errors = infcx.reported_trait_errors.borrow();
+    let mut keys = vec![];
+    let mut members = vec![];
+    for &index in group {
+        let key = TraitErrorKey::from_error(infcx, &errors[index]);
+        if !reported_trait_errors.contains(&key) && !keys.contains(&key) {
+            keys.push(key);
+            members.push(index);
+        }
+    }
+    members
+}
+
+/// Reports the errors of `members`, which all lack a trait for the same
+/// type at the same place, as a single error naming every missing bound.
+fn report_missing_bounds<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                   errors: &[FulfillmentError<'tcx>],
+                                   members: &[usize])
+{
+    let tcx = infcx.tcx;
+    for &index in members {
+        infcx.reported_trait_errors.borrow_mut()
+             .insert(TraitErrorKey::from_error(infcx, &errors[index]));
+    }
+    let obligation = &errors[members[0]].obligation;
+    let rfc1214_level = if obligation.cause.code.is_rfc1214() {
+        rfc_1214_level(tcx, obligation.cause.body_id)
+    } else {
+        lint::Warn
+    };
+    if rfc1214_level == lint::Allow {
+        return;
+    }
+
+    let trait_refs: Vec<ty::PolyTraitRef<'tcx>> = members.iter().filter_map(|&index| {
+        match errors[index].obligation.predicate {
+            ty::Predicate::Trait(ref data) => {
+                Some(infcx.resolve_type_vars_if_possible(&data.to_poly_trait_ref()))
+            }
+            _ => None,
+        }
+    }).collect();
+    let named_trait_refs: Vec<_> = trait_refs.iter().map(|trait_ref| {
+        with_source_param_names(tcx, obligation.cause.body_id, trait_ref)
+    }).collect();
+    let bounds: Vec<String> = named_trait_refs.iter().map(|r| format!("`{}`", r)).collect();
+    let mut report = trait_error_report!(
+        is_warning(tcx.sess, obligation) && rfc1214_level == lint::Warn,
+        obligation.cause.span, E0277,
+        "the type `{}` does not satisfy the following bounds: {}",
+        named_trait_refs[0].self_ty(), bounds.join(", "));
+
+    for trait_ref in &trait_refs {
+        record_unimplemented_trait(tcx, trait_ref);
+        let custom = report_on_unimplemented(infcx, &trait_ref.0, &obligation.cause,
+                                             &mut report.attribute_errors);
+        for s in &custom.labels {
+            report.span_note(obligation.cause.span, s);
+        }
+        for s in &custom.notes {
+            report.note(obligation.cause.span, s);
+        }
+        for s in &custom.helps {
+            report.help(obligation.cause.span, s);
+        }
+    }
+
+    // Explain RFC 1214 only on the first warning for each predicate.
+    let explained = is_rfc1214 && !members.iter().fold(false, |fresh, &index| {
+        let predicate = TraitErrorKey::from_error(infcx, &errors[index]).predicate;
+        infcx.rfc1214_noted_predicates.borrow_mut().insert(predicate) || fresh
+    });
+    infcx.suppress_rfc1214_note.set(explained);
+    note_obligation_cause(infcx, obligation, &mut report);
+    infcx.suppress_rfc1214_note.set(false);
+
+    for trait_ref in &trait_refs {
+        suggest_bound_on_type_param(tcx, &obligation.cause, trait_ref, &mut report);
+    }
+    report.emit(tcx.sess);
+}
+
+/// `code` without the `RFC1214` and `FeatureMigration` wrappers, which
+/// only decide whether its failure is a warning.
+fn unwrapped_cause_code<'a, 'tcx>(code: &'a ObligationCauseCode<'tcx>)
+                                  -> &'a ObligationCauseCode<'tcx>
+{
+    match *code {
+        ObligationCauseCode::RFC1214(ref subcode) |
+        ObligationCauseCode::FeatureMigration(_, ref subcode) => unwrapped_cause_code(subcode),
+        _ => code,
+    }
+}
+
+/// The trait references that the obligation with this cause was
+/// derived from, innermost first.
+fn derived_trait_refs<'tcx>(code: &ObligationCauseCode<'tcx>) -> Vec<ty::PolyTraitRef<'tcx>> {
+    let mut trait_refs = vec![];
+    let mut code = code;
+    loop {
+        code = match *code {
+            ObligationCauseCode::RFC1214(ref subcode) |
+            ObligationCauseCode::FeatureMigration(_, ref subcode) => &**subcode,
+            ObligationCauseCode::BuiltinDerivedObligation(ref data) |
+            ObligationCauseCode::ImplDerivedObligation(ref data) => {
+                trait_refs.push(data.parent_trait_ref.clone());
+                &*data.parent_code
+            }
+            _ => return trait_refs,
+        };
     }
 }
 
-fn report_fulfillment_error<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
-                                      error: &FulfillmentError<'tcx>) {
+/// Builds the report for a single error, returning `None` if it is not
+/// reported, e.g. as a duplicate of one already reported.
+fn fulfillment_error_report<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                      error: &FulfillmentError<'tcx>)
+                                      -> Option<TraitErrorReport> {
     let error_key = TraitErrorKey::from_error(infcx, error);
+    let erased_predicate = error_key.predicate.clone();
     debug!("report_fulfillment_errors({:?}) - key={:?}",
            error, error_key);
     if !infcx.reported_trait_errors.borrow_mut().insert(error_key) {
         debug!("report_fulfillment_errors: skipping duplicate");
-        return;
+        return None;
     }
-    match error.code {
+    if follows_object_safety_error(infcx, error) {
+        debug!("report_fulfillment_errors: follows from an object-safety error");
+        return None;
+    }
+    let is_rfc1214 = error.obligation.cause.code.is_rfc1214();
+    let rfc1214_level = if is_rfc1214 {
+        rfc_1214_level(infcx.tcx, error.obligation.cause.body_id)
+    } else {
+        lint::Warn
+    };
+    if rfc1214_level == lint::Allow {
+        debug!("report_fulfillment_errors: RFC1214 warnings allowed here");
+        return None;
+    }
+    // Explain RFC 1214 only on the first warning for each predicate.
+    let explained = is_rfc1214 &&
+        !infcx.rfc1214_noted_predicates.borrow_mut().insert(erased_predicate);
+    infcx.suppress_rfc1214_note.set(explained);
+    let report = match error.code {
         FulfillmentErrorCode::CodeSelectionError(ref e) => {
-            report_selection_error(infcx, &error.obligation, e);
+            selection_error_report(infcx, &error.obligation, e)
         }
         FulfillmentErrorCode::CodeProjectionError(ref e) => {
-            report_projection_error(infcx, &error.obligation, e);
+            build_projection_error_report(infcx, &error.obligation, e)
         }
         FulfillmentErrorCode::CodeAmbiguity => {
-            maybe_report_ambiguity(infcx, &error.obligation);
+            build_ambiguity_report(infcx, &error.obligation)
+        }
+    };
+    infcx.suppress_rfc1214_note.set(false);
+    report.map(|mut report| {
+        // `deny(rfc_1214)` and `forbid(rfc_1214)` make these hard errors.
+        if rfc1214_level != lint::Warn {
+            report.is_warning = false;
         }
+        report
+    })
+}
+
+/// Whether `error` is a selection error for the object type of a trait
+/// that was reported as not object-safe at the same span, and so is
+/// already explained.
+fn follows_object_safety_error<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                         error: &FulfillmentError<'tcx>) -> bool {
+    let self_ty = match (&error.code, &error.obligation.predicate) {
+        (&FulfillmentErrorCode::CodeSelectionError(_), &ty::Predicate::Trait(ref data)) => {
+            infcx.resolve_type_vars_if_possible(&data.0.self_ty())
+        }
+        _ => return false,
+    };
+    match self_ty.sty {
+        ty::TyTrait(ref data) => {
+            infcx.reported_object_safety_errors.borrow()
+                 .contains(&(data.principal_def_id(), error.obligation.cause.span))
+        }
+        _ => false,
     }
 }
 
-fn is_warning<T>(obligation: &Obligation<T>) -> bool {
-    obligation.cause.code.is_rfc1214()
+/// Whether a failure of `obligation` is reported as a warning: because
+/// of RFC 1214, or because the feature that introduced it is listed in
+/// `-Z feature-trait-warnings`.
+fn is_warning<T>(sess: &Session, obligation: &Obligation<T>) -> bool {
+    obligation.cause.code.is_rfc1214() || is_feature_warning(sess, &obligation.cause.code)
+}
+
+fn is_feature_warning(sess: &Session, code: &ObligationCauseCode) -> bool {
+    code.migrating_feature().map_or(false, |feature| {
+        sess.opts.debugging_opts.feature_trait_warnings.iter().any(|f| *f == *feature.as_str())
+    })
+}
+
+/// RFC1214 warnings can be silenced with `#[allow(rfc_1214)]` on any
+/// item or module enclosing the code, or with `-A rfc_1214`, and made
+/// errors with `deny` or `forbid`. The lint levels proper are not
+/// computed until after type checking, so the attributes are consulted
+/// directly.
+fn rfc_1214_level(tcx: &ty::ctxt, node_id: ast::NodeId) -> lint::Level {
+    let lint_name = builtin::RFC_1214.name_lower();
+    let mut id = node_id;
+    loop {
+        let attrs = if id == ast::CRATE_NODE_ID {
+            &tcx.map.krate().attrs[..]
+        } else {
+            tcx.map.attrs(id)
+        };
+        for result in lint::gather_attrs(attrs) {
+            if let Ok((ref name, level, _)) = result {
+                if **name == lint_name[..] {
+                    return level;
+                }
+            }
+        }
+        let parent = tcx.map.get_parent(id);
+        if parent == id {
+            break;
+        }
+        id = parent;
+    }
+    let lint_id = lint::LintId::of(builtin::RFC_1214);
+    tcx.sess.lint_store.borrow().get_level_source(lint_id).0
 }
 
 pub fn report_projection_error<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
                                          obligation: &PredicateObligation<'tcx>,
                                          error: &MismatchedProjectionTypes<'tcx>)
+{
+    if let Some(report) = build_projection_error_report(infcx, obligation, error) {
+        report.emit(infcx.tcx.sess);
+    }
+}
+
+/// Builds the report for a projection error (E0271), or returns `None`
+/// if it follows from an error already reported.
+fn build_projection_error_report<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                           obligation: &PredicateObligation<'tcx>,
+                                           error: &MismatchedProjectionTypes<'tcx>)
+                                           -> Option<TraitErrorReport>
 {
     let predicate =
         infcx.resolve_type_vars_if_possible(&obligation.predicate);
+    let is_warning = is_warning(infcx.tcx.sess, obligation);
 
     // The TyError created by normalize_to_error can end up being unified
     // into all obligations: for example, if our obligation is something
     // like `$X = <() as Foo<$X>>::Out` and () does not implement Foo<_>,
     // then $X will be unified with TyError, but the error still needs to be
     // reported.
-    if !infcx.tcx.sess.has_errors() || !predicate.references_error() {
-        span_err_or_warn!(
-            is_warning(obligation), infcx.tcx.sess, obligation.cause.span, E0271,
-            "type mismatch resolving `{}`: {}",
-            predicate,
-            error.err);
-        note_obligation_cause(infcx, obligation);
+    if infcx.tcx.sess.has_errors() && predicate.references_error() {
+        return None;
     }
-}
-
-fn report_on_unimplemented<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
-                                     trait_ref: &TraitRef<'tcx>,
-                                     span: Span) -> Option<String> {
-    let def_id = trait_ref.def_id;
-    let mut report = None;
-    for item in infcx.tcx.get_attrs(def_id).iter() {
-        if item.check_name("rustc_on_unimplemented") {
-            let err_sp = item.meta().span.substitute_dummy(span);
-            let def = infcx.tcx.lookup_trait_def(def_id);
-            let trait_str = def.trait_ref.to_string();
-            if let Some(ref istring) = item.value_str() {
-                let mut generic_map = def.generics.types.iter_enumerated()
-                                         .map(|(param, i, gen)| {
-                                               (gen.name.as_str().to_string(),
-                                                trait_ref.substs.types.get(param, i)
-                                                         .to_string())
-                                              }).collect::<FnvHashMap<String, String>>();
-                generic_map.insert("Self".to_string(),
-                                   trait_ref.self_ty().to_string());
-                let parser = Parser::new(&istring);
-                let mut errored = false;
-                let err: String = parser.filter_map(|p| {
-                    match p {
-                        Piece::String(s) => Some(s),
-                        Piece::NextArgument(a) => match a.position {
-                            Position::ArgumentNamed(s) => match generic_map.get(s) {
-                                Some(val) => Some(val),
-                                None => {
-                                    span_err!(infcx.tcx.sess, err_sp, E0272,
-                                                   "the #[rustc_on_unimplemented] \
-                                                            attribute on \
-                                                            trait definition for {} refers to \
-                                                            non-existent type parameter {}",
-                                                           trait_str, s);
-                                    errored = true;
-                                    None
-                                }
-                            },
-                            _ => {
-                                     span_err!(infcx.tcx.sess, err_sp, E0273,
-                                               "the #[rustc_on_unimplemented] \
-                                                        attribute on \
-                                                        trait definition for {} must have named \
-                                                        format arguments, \
-                                                        eg `#[rustc_on_unimplemented = \
-                                                        \"foo {{T}}\"]`",
-                                                       trait_str);
-                                errored = true;
-                                None
-                            }
-                        }
-                    }
-                }).collect();
-                // Report only if the format string checks out
-                if !errored {
-                    report = Some(err);
+    let mut report = match iterator_item_mismatch(infcx, obligation, &predicate) {
+        Some(mismatch) => {
+            trait_error_report!(
+                is_warning, obligation.cause.span, E0271,
+                "type mismatch resolving `{}`: {}",
+                predicate,
+                mismatch)
+        }
+        None => match projection_mismatch(infcx, &predicate, error) {
+            Some((projection, expected, found)) => {
+                let mut report = trait_error_report!(
+                    is_warning, obligation.cause.span, E0271,
+                    "type mismatch resolving `{}`: expected `{}` to be `{}`, found `{}`",
+                    predicate,
+                    projection,
+                    expected,
+                    found);
+                if !expected.has_infer_types() && !found.has_infer_types() {
+                    report.note(obligation.cause.span,
+                                &format!("expected type `{}`", expected));
+                    report.note(obligation.cause.span,
+                                &format!("   found type `{}`", found));
                 }
-            } else {
-                span_err!(infcx.tcx.sess, err_sp, E0274,
-                                        "the #[rustc_on_unimplemented] attribute on \
-                                                 trait definition for {} must have a value, \
-                                                 eg `#[rustc_on_unimplemented = \"foo\"]`",
-                                                 trait_str);
+                report
             }
-            break;
+            None => {
+                trait_error_report!(
+                    is_warning, obligation.cause.span, E0271,
+                    "type mismatch resolving `{}`: {}",
+                    predicate,
+                    error.err)
+            }
+        },
+    };
+    if let ty::Predicate::Projection(ref data) = predicate {
+        note_projection_input_expr(infcx, &obligation.cause, data, &mut report);
+        note_conflicting_projection_bounds(infcx, &obligation.cause, data, &mut report);
+        if infcx.tcx.sess.verbose() {
+            note_projection_impl_rejections(infcx, obligation, data, &mut report);
         }
     }
-    report
+    note_obligation_cause(infcx, obligation, &mut report);
+    Some(report)
 }
 
-/// Reports that an overflow has occurred and halts compilation. We
-/// halt compilation unconditionally because it is important that
-/// overflows never be masked -- they basically represent computations
-/// whose result could not be truly determined and thus we can't say
-/// if the program type checks or not -- and they are unusual
-/// occurrences in any case.
-pub fn report_overflow_error<'a, 'tcx, T>(infcx: &InferCtxt<'a, 'tcx>,
-                                          obligation: &Obligation<'tcx, T>)
-                                          -> !
-    where T: fmt::Display + TypeFoldable<'tcx> + HasTypeFlags
+/// When the mismatched projection was required by a call, points at the
+/// argument whose type the projection's self type was inferred from.
+fn note_projection_input_expr<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                        cause: &ObligationCause<'tcx>,
+                                        data: &ty::PolyProjectionPredicate<'tcx>,
+                                        report: &mut TraitErrorReport)
 {
-    let predicate =
-        infcx.resolve_type_vars_if_possible(&obligation.predicate);
-    span_err!(infcx.tcx.sess, obligation.cause.span, E0275,
-              "overflow evaluating the requirement `{}`",
-              predicate);
+    let tcx = infcx.tcx;
+    let self_ty = data.0.projection_ty.trait_ref.self_ty();
+    if self_ty.needs_infer() || self_ty.references_error() {
+        return;
+    }
+    let mut finder = CallFinder { span: cause.span, call: None };
+    match tcx.map.find(enclosing_item_id(tcx, cause.body_id)) {
+        Some(ast_map::NodeItem(item)) => finder.visit_item(item),
+        Some(ast_map::NodeTraitItem(item)) => finder.visit_trait_item(item),
+        Some(ast_map::NodeImplItem(item)) => finder.visit_impl_item(item),
+        _ => return,
+    }
+    let args = match finder.call.map(|expr| &expr.node) {
+        Some(&hir::ExprCall(_, ref args)) |
+        Some(&hir::ExprMethodCall(_, _, ref args)) => args,
+        _ => return,
+    };
 
-    suggest_new_overflow_limit(infcx.tcx, obligation.cause.span);
+    let tables = infcx.tables.borrow();
+    for arg in args {
+        let arg_ty = match tables.node_types.get(&arg.id) {
+            Some(&arg_ty) => infcx.resolve_type_vars_if_possible(&arg_ty),
+            None => continue,
+        };
+        if arg_ty.walk().any(|ty| ty == self_ty) {
+            report.span_note(arg.span,
+                             &format!("this expression has type `{}`", arg_ty));
+            return;
+        }
+    }
+}
 
-    note_obligation_cause(infcx, obligation);
+/// Under `-Z verbose`, lists the impls that might have defined the
+/// associated type of a mismatched projection, and why each was not used.
+fn note_projection_impl_rejections<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                             obligation: &PredicateObligation<'tcx>,
+                                             data: &ty::PolyProjectionPredicate<'tcx>,
+                                             report: &mut TraitErrorReport)
+{
+    let tcx = infcx.tcx;
+    let projection_obligation = obligation.with(data.clone());
+    let rejections = project::projection_impl_rejections(&mut SelectionContext::new(infcx),
+                                                         &projection_obligation);
+    for (impl_def_id, rejection) in rejections {
+        let impl_trait_ref = match tcx.impl_trait_ref(impl_def_id) {
+            Some(impl_trait_ref) => impl_trait_ref,
+            None => continue,
+        };
+        let reason = match rejection {
+            project::ProjectionImplRejection::HeaderMismatch => {
+                "does not match: its self type or trait parameters differ".to_string()
+            }
+            project::ProjectionImplRejection::WhereClauseUnsatisfied => {
+                "does not apply: one of its where-clauses does not hold".to_string()
+            }
+            project::ProjectionImplRejection::DefinesAs(ty) => {
+                format!("applies, but defines `{} = {}`", data.item_name(), ty)
+            }
+        };
+        let msg = format!("the impl of `{}` for `{}` {}",
+                          impl_trait_ref, impl_trait_ref.self_ty(), reason);
+        match tcx.map.as_local_node_id(impl_def_id) {
+            Some(node_id) => report.span_note(tcx.map.span(node_id), &msg),
+            None => report.note(obligation.cause.span, &msg),
+        }
+    }
+}
 
-    infcx.tcx.sess.abort_if_errors();
-    unreachable!();
+/// Splits a projection mismatch into the projection, spelled out as
+/// `<T as Trait>::Name`, the type it was required to be, and the type
+/// it actually normalized to. Returns `None` unless the mismatch is
+/// between exactly those two types.
+fn projection_mismatch<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                 predicate: &ty::Predicate<'tcx>,
+                                 error: &MismatchedProjectionTypes<'tcx>)
+                                 -> Option<(String, Ty<'tcx>, Ty<'tcx>)>
+{
+    let data = match *predicate {
+        ty::Predicate::Projection(ref data) => match infcx.tcx.no_late_bound_regions(data) {
+            Some(data) => data,
+            None => return None,
+        },
+        _ => return None,
+    };
+    let values = match error.err {
+        TypeError::Sorts(ref values) => values,
+        _ => return None,
+    };
+    let expected = infcx.resolve_type_vars_if_possible(&values.expected);
+    let found = infcx.resolve_type_vars_if_possible(&values.found);
+    // The normalized type is unified against the required one, so the
+    // required type is on one side of the mismatch.
+    let normalized = if found == data.ty {
+        expected
+    } else if expected == data.ty {
+        found
+    } else {
+        return None;
+    };
+    let trait_ref = data.projection_ty.trait_ref;
+    let projection = format!("<{} as {}>::{}",
+                             trait_ref.self_ty(),
+                             trait_ref,
+                             data.projection_ty.item_name);
+    Some((projection, data.ty, normalized))
 }
 
-pub fn report_selection_error<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
-                                        obligation: &PredicateObligation<'tcx>,
-                                        error: &SelectionError<'tcx>)
+/// `Item` mismatches of `Iterator` and `IntoIterator` are among the most
+/// common projection errors, so describe them in terms of what the
+/// iterator yields rather than as a bare type mismatch.
+fn iterator_item_mismatch<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                    obligation: &PredicateObligation<'tcx>,
+                                    predicate: &ty::Predicate<'tcx>)
+                                    -> Option<String>
 {
-    let is_warning = is_warning(obligation);
-    match *error {
-        SelectionError::Unimplemented => {
-            if let ObligationCauseCode::CompareImplMethodObligation = obligation.cause.code {
-                span_err_or_warn!(
-                    is_warning, infcx.tcx.sess, obligation.cause.span, E0276,
-                    "the requirement `{}` appears on the impl \
-                     method but not on the corresponding trait method",
-                    obligation.predicate);
-            } else {
-                match obligation.predicate {
-                    ty::Predicate::Trait(ref trait_predicate) => {
-                        let trait_predicate =
-                            infcx.resolve_type_vars_if_possible(trait_predicate);
+    let tcx = infcx.tcx;
+    let data = match *predicate {
+        ty::Predicate::Projection(ref data) => match tcx.no_late_bound_regions(data) {
+            Some(data) => data,
+            None => return None,
+        },
+        _ => return None,
+    };
+    if data.projection_ty.item_name.as_str() != "Item" {
+        return None;
+    }
+    let self_ty = data.projection_ty.trait_ref.self_ty();
+    let yielding = match &tcx.item_path_str(data.projection_ty.trait_ref.def_id)[..] {
+        "core::iter::Iterator" => format!("the iterator `{}` yields", self_ty),
+        "core::iter::IntoIterator" => format!("iterating over `{}` yields", self_ty),
+        _ => return None,
+    };
 
-                        if !infcx.tcx.sess.has_errors() || !trait_predicate.references_error() {
-                            let trait_ref = trait_predicate.to_poly_trait_ref();
-                            span_err_or_warn!(
-                                is_warning, infcx.tcx.sess, obligation.cause.span, E0277,
-                                "the trait `{}` is not implemented for the type `{}`",
-                                trait_ref, trait_ref.self_ty());
-
-                            // Check if it has a custom "#[rustc_on_unimplemented]"
-                            // error message, report with that message if it does
-                            let custom_note = report_on_unimplemented(infcx, &trait_ref.0,
-                                                                      obligation.cause.span);
-                            if let Some(s) = custom_note {
-                                infcx.tcx.sess.fileline_note(obligation.cause.span, &s);
-                            }
-                            note_obligation_cause(infcx, obligation);
-                        }
-                    }
+    let yielded = infcx.probe(|_| {
+        let mut selcx = SelectionContext::new(infcx);
+        let normalized = project::normalize_projection_type(&mut selcx,
+                                                            data.projection_ty.clone(),
+                                                            obligation.cause.clone(),
+                                                            0);
+        infcx.resolve_type_vars_if_possible(&normalized.value)
+    });
+    if yielded.has_infer_types() || yielded.references_error() {
+        return None;
+    }
+    Some(format!("{} `{}`, but `{}` was expected here", yielding, yielded, data.ty))
+}
 
-                    ty::Predicate::Equate(ref predicate) => {
-                        let predicate = infcx.resolve_type_vars_if_possible(predicate);
-                        let err = infcx.equality_predicate(obligation.cause.span,
-                                                           &predicate).err().unwrap();
-                        span_err_or_warn!(
-                            is_warning, infcx.tcx.sess, obligation.cause.span, E0278,
-                            "the requirement `{}` is not satisfied (`{}`)",
-                            predicate,
-                            err);
-                        note_obligation_cause(infcx, obligation);
-                    }
+/// When the where-clauses in scope bind the same projection to
+/// different types (`Item = A` and `Item = B`), the mismatch is only a
+/// symptom; report both bindings and point at where they were written.
+fn note_conflicting_projection_bounds<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                                cause: &ObligationCause<'tcx>,
+                                                data: &ty::PolyProjectionPredicate<'tcx>,
+                                                report: &mut TraitErrorReport)
+{
+    let tcx = infcx.tcx;
+    let mut bound_tys: Vec<Ty<'tcx>> = vec![];
+    for caller_bound in &infcx.parameter_environment.caller_bounds {
+        if let ty::Predicate::Projection(ref bound_data) = *caller_bound {
+            if bound_data.0.projection_ty == data.0.projection_ty &&
+               !bound_tys.contains(&bound_data.0.ty) {
+                bound_tys.push(bound_data.0.ty);
+            }
+        }
+    }
+    if bound_tys.len() < 2 {
+        return;
+    }
 
-                    ty::Predicate::RegionOutlives(ref predicate) => {
-                        let predicate = infcx.resolve_type_vars_if_possible(predicate);
-                        let err = infcx.region_outlives_predicate(obligation.cause.span,
-                                                                  &predicate).err().unwrap();
-                        span_err_or_warn!(
-                            is_warning, infcx.tcx.sess, obligation.cause.span, E0279,
-                            "the requirement `{}` is not satisfied (`{}`)",
-                            predicate,
-                            err);
-                        note_obligation_cause(infcx, obligation);
-                    }
+    let listing = bound_tys.iter()
+                           .map(|ty| format!("`{}`", ty))
+                           .collect::<Vec<_>>()
+                           .join(" and ");
+    report.note(
+        cause.span,
+        &format!("the projection `{}` is bound to both {} by the where-clauses in scope",
+                 data.0.projection_ty,
+                 listing));
 
-                    ty::Predicate::Projection(..) | ty::Predicate::TypeOutlives(..) => {
-                        let predicate =
-                            infcx.resolve_type_vars_if_possible(&obligation.predicate);
-                        span_err_or_warn!(
-                            is_warning, infcx.tcx.sess, obligation.cause.span, E0280,
-                            "the requirement `{}` is not satisfied",
-                            predicate);
-                        note_obligation_cause(infcx, obligation);
-                    }
+    if let Some(generics) = enclosing_fn_generics(tcx, cause.body_id) {
+        let mut finder = AssocTypeBindingFinder { name: data.item_name(), spans: vec![] };
+        finder.visit_generics(generics);
+        for span in finder.spans {
+            report.span_note(span,
+                             &format!("`{}` is bound here", data.item_name()));
+        }
+    }
+}
 
-                    ty::Predicate::ObjectSafe(trait_def_id) => {
-                        let violations = object_safety_violations(
-                            infcx.tcx, trait_def_id);
-                        report_object_safety_error(infcx.tcx,
-                                                   obligation.cause.span,
-                                                   trait_def_id,
-                                                   violations,
-                                                   is_warning);
-                        note_obligation_cause(infcx, obligation);
-                    }
+/// Collects the spans of all `Name = Type` bindings for one associated
+/// type name, e.g. the `Item = u32` in `T: Iterator<Item = u32>`.
+struct AssocTypeBindingFinder {
+    name: ast::Name,
+    spans: Vec<Span>,
+}
+
+impl<'v> Visitor<'v> for AssocTypeBindingFinder {
+    fn visit_assoc_type_binding(&mut self, binding: &'v hir::TypeBinding) {
+        if binding.name == self.name {
+            self.spans.push(binding.span);
+        }
+        visit::walk_assoc_type_binding(self, binding);
+    }
+}
+
+/// Returns the generics of the fn or method whose body incurred an
+/// obligation (identified by the cause's `body_id`), if it is local.
+fn enclosing_fn_generics<'a, 'tcx>(tcx: &'a ty::ctxt<'tcx>,
+                                   body_id: ast::NodeId)
+                                   -> Option<&'tcx hir::Generics>
+{
+    enclosing_fn_sig(tcx, body_id).map(|(_, generics)| generics)
+}
+
+/// Returns the id of the item, trait item or impl item whose body
+/// incurred an obligation.
+fn enclosing_item_id(tcx: &ty::ctxt, body_id: ast::NodeId) -> ast::NodeId {
+    match tcx.map.find(body_id) {
+        Some(ast_map::NodeItem(..)) |
+        Some(ast_map::NodeTraitItem(..)) |
+        Some(ast_map::NodeImplItem(..)) => body_id,
+        _ => tcx.map.get_parent(body_id),
+    }
+}
+
+/// Returns the declaration and generics of the fn or method whose body
+/// incurred an obligation, if it is local.
+fn enclosing_fn_sig<'a, 'tcx>(tcx: &'a ty::ctxt<'tcx>,
+                              body_id: ast::NodeId)
+                              -> Option<(&'tcx hir::FnDecl, &'tcx hir::Generics)>
+{
+    match tcx.map.find(enclosing_item_id(tcx, body_id)) {
+        Some(ast_map::NodeItem(item)) => match item.node {
+            hir::ItemFn(ref decl, _, _, _, ref generics, _) => Some((&**decl, generics)),
+            _ => None,
+        },
+        Some(ast_map::NodeTraitItem(item)) => match item.node {
+            hir::MethodTraitItem(ref sig, _) => Some((&*sig.decl, &sig.generics)),
+            _ => None,
+        },
+        Some(ast_map::NodeImplItem(item)) => match item.node {
+            hir::MethodImplItem(ref sig, _) => Some((&*sig.decl, &sig.generics)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns the generics of the fn or method whose body incurred an
+/// obligation, and those of the impl or trait around it, if local.
+fn enclosing_generics<'a, 'tcx>(tcx: &'a ty::ctxt<'tcx>,
+                                body_id: ast::NodeId)
+                                -> (Option<&'tcx hir::Generics>, Option<&'tcx hir::Generics>)
+{
+    let item_id = enclosing_item_id(tcx, body_id);
+    let fn_generics = enclosing_fn_sig(tcx, body_id).map(|(_, generics)| generics);
+    let parent_generics = match tcx.map.find(tcx.map.get_parent(item_id)) {
+        Some(ast_map::NodeItem(item)) => match item.node {
+            hir::ItemImpl(_, _, ref generics, _, _, _) |
+            hir::ItemTrait(_, ref generics, _, _) => Some(generics),
+            _ => None,
+        },
+        _ => None,
+    };
+    (fn_generics, parent_generics)
+}
+
+/// Renames the type parameters in `value` to the names they were
+/// declared with in the enclosing fn and its impl or trait. A parameter
+/// can carry another item's name for the same position, e.g. the trait
+/// method's when checking an impl method against it.
+fn with_source_param_names<'tcx, T>(tcx: &ty::ctxt<'tcx>,
+                                    body_id: ast::NodeId,
+                                    value: &T)
+                                    -> T
+    where T: TypeFoldable<'tcx>
+{
+    let (fn_generics, parent_generics) = enclosing_generics(tcx, body_id);
+    let declared_name = |space, idx: u32| {
+        let generics = match space {
+            subst::FnSpace => fn_generics,
+            subst::TypeSpace => parent_generics,
+            subst::SelfSpace => None,
+        };
+        generics.and_then(|generics| generics.ty_params.get(idx as usize))
+                .map(|ty_param| ty_param.name)
+    };
+    let mut folder = BottomUpFolder {
+        tcx: tcx,
+        fldop: |ty: Ty<'tcx>| match ty.sty {
+            ty::TyParam(ref p) => match declared_name(p.space, p.idx) {
+                Some(name) if name != p.name => tcx.mk_param(p.space, p.idx, name),
+                _ => ty,
+            },
+            _ => ty,
+        },
+    };
+    value.fold_with(&mut folder)
+}
+
+/// When the unimplemented trait is wanted for a type parameter of the
+/// enclosing fn or of the impl or trait around it, suggest requiring it.
+fn suggest_bound_on_type_param<'tcx>(tcx: &ty::ctxt<'tcx>,
+                                     cause: &ObligationCause<'tcx>,
+                                     trait_ref: &ty::PolyTraitRef<'tcx>,
+                                     report: &mut TraitErrorReport)
+{
+    let param = match trait_ref.self_ty().sty {
+        ty::TyParam(ref param) if param.space != subst::SelfSpace => param.name,
+        _ => return,
+    };
+    if tcx.no_late_bound_regions(trait_ref).is_none() ||
+       trait_ref.input_types().iter().any(|t| t.needs_infer()) {
+        return;
+    }
+
+    let (fn_generics, parent_generics) = enclosing_generics(tcx, cause.body_id);
+    let declared = fn_generics.into_iter().chain(parent_generics).any(|generics| {
+        generics.ty_params.iter().any(|ty_param| ty_param.name == param)
+    });
+    if !declared {
+        return;
+    }
+
+    let bound = format!("{}: {}", param, trait_ref);
+    report.help(
+        cause.span,
+        &format!("consider adding a `where {}` bound, or declaring the type parameter \
+                  as `{}`",
+                 bound,
+                 bound));
+}
+
+/// If the failing trait reference leaves some type parameters of the
+/// trait at their defaults (as `T: Add` means `T: Add<T>`), suggests
+/// giving them explicitly: with the arguments of an impl that does hold
+/// for the self type if there is one, or else just the general syntax.
+fn suggest_overriding_default_type_param<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                                   cause: &ObligationCause<'tcx>,
+                                                   trait_ref: &ty::PolyTraitRef<'tcx>,
+                                                   report: &mut TraitErrorReport)
+{
+    let tcx = infcx.tcx;
+    let trait_ref = match tcx.no_late_bound_regions(trait_ref) {
+        Some(trait_ref) => trait_ref,
+        None => return,
+    };
+    if trait_ref.input_types().iter().any(|t| t.needs_infer() || t.references_error()) {
+        return;
+    }
+    let trait_def = tcx.lookup_trait_def(trait_ref.def_id);
+    let defaulted: Vec<String> =
+        trait_def.generics.types.get_slice(subst::TypeSpace).iter().filter(|def| {
+            def.default.map_or(false, |default| {
+                default.subst(tcx, trait_ref.substs) ==
+                    *trait_ref.substs.types.get(def.space, def.index as usize)
+            })
+        }).map(|def| def.name.to_string()).collect();
+    if defaulted.is_empty() {
+        return;
+    }
+
+    let mut candidates = vec![];
+    trait_def.for_each_relevant_impl(tcx, trait_ref.self_ty(), |impl_def_id| {
+        if let Some(impl_trait_ref) = tcx.impl_trait_ref(impl_def_id) {
+            if impl_trait_ref.self_ty() == trait_ref.self_ty() &&
+               !impl_trait_ref.needs_subst() &&
+               trait_ref_may_hold(infcx, cause, impl_trait_ref) {
+                candidates.push(impl_trait_ref);
+            }
+        }
+    });
+
+    let candidate = match candidates.len() {
+        1 => candidates[0],
+        _ => {
+            let all_params = trait_def.generics.types.get_slice(subst::TypeSpace).iter()
+                                                     .map(|def| def.name.to_string())
+                                                     .collect::<Vec<_>>();
+            report.help(
+                cause.span,
+                &format!("`{}` falls back to the default for {}; to require a different \
+                          type, give it explicitly, as in `{}<{}>`",
+                         trait_ref,
+                         defaulted.iter().map(|name| format!("`{}`", name))
+                                  .collect::<Vec<_>>().join(", "),
+                         tcx.item_path_str(trait_ref.def_id),
+                         all_params.join(", ")));
+            return;
+        }
+    };
+
+    let msg = format!("`{}` does implement `{}`; consider giving the defaulted type \
+                       parameter explicitly",
+                      trait_ref.self_ty(), candidate);
+    match defaulted_bound_span(tcx, cause, trait_ref.def_id) {
+        Some(span) => report.suggestion(span, &msg, candidate.to_string()),
+        None => report.help(cause.span, &msg),
+    }
+}
+
+/// The span of the only bound naming the trait `trait_def_id` without
+/// type arguments among the bounds of the fn that `cause` came from.
+fn defaulted_bound_span<'tcx>(tcx: &ty::ctxt<'tcx>,
+                              cause: &ObligationCause<'tcx>,
+                              trait_def_id: DefId)
+                              -> Option<Span>
+{
+    let code = unwrapped_cause_code(&cause.code);
+    let generics = match *code {
+        ObligationCauseCode::ItemObligation(item_def_id) => {
+            match tcx.map.as_local_node_id(item_def_id).and_then(|id| enclosing_fn_sig(tcx, id)) {
+                Some((_, generics)) => generics,
+                None => return None,
+            }
+        }
+        _ => return None,
+    };
+
+    let mut bounds: Vec<&hir::TyParamBound> = vec![];
+    for ty_param in generics.ty_params.iter() {
+        bounds.extend(ty_param.bounds.iter());
+    }
+    for predicate in &generics.where_clause.predicates {
+        if let hir::WherePredicate::BoundPredicate(ref data) = *predicate {
+            bounds.extend(data.bounds.iter());
+        }
+    }
+    let spans: Vec<Span> = bounds.into_iter().filter_map(|bound| match *bound {
+        hir::TraitTyParamBound(ref poly_trait_ref, _) => {
+            let path = &poly_trait_ref.trait_ref.path;
+            let names_trait = match tcx.def_map.borrow().get(&poly_trait_ref.trait_ref.ref_id) {
+                Some(&def::PathResolution { base_def: def::DefTrait(did), depth: 0, .. }) => {
+                    did == trait_def_id
+                }
+                _ => false,
+            };
+            let has_types = path.segments.last()
+                                .map_or(false, |seg| !seg.parameters.types().is_empty());
+            if names_trait && !has_types { Some(path.span) } else { None }
+        }
+        _ => None,
+    }).collect();
+    if spans.len() == 1 { Some(spans[0]) } else { None }
+}
+
+/// If a failing lifetime requirement involves a lifetime that elision
+/// picked for the enclosing fn, show which argument types had their
+/// lifetimes elided and suggest naming them, so that the requirement
+/// can be written into the signature.
+fn suggest_naming_elided_lifetimes<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                             cause: &ObligationCause<'tcx>,
+                                             regions: &[ty::Region],
+                                             report: &mut TraitErrorReport)
+{
+    let tcx = infcx.tcx;
+    let involves_elided = regions.iter().any(|r| match *r {
+        ty::ReFree(ty::FreeRegion { bound_region: ty::BrAnon(_), .. }) => true,
+        _ => false,
+    });
+    if !involves_elided {
+        return;
+    }
+    let (decl, generics) = match enclosing_fn_sig(tcx, cause.body_id) {
+        Some(sig) => sig,
+        None => return,
+    };
+    // With lifetimes already in scope we can't pick a fresh name safely.
+    if !generics.lifetimes.is_empty() {
+        return;
+    }
+
+    let elided: Vec<(Span, String)> = decl.inputs.iter().filter_map(|arg| {
+        match arg.ty.node {
+            hir::TyRptr(None, _) => {
+                tcx.sess.codemap().span_to_snippet(arg.ty.span).ok()
+                                  .map(|snippet| (arg.ty.span, snippet))
+            }
+            _ => None,
+        }
+    }).collect();
+    if elided.is_empty() {
+        return;
+    }
+
+    let listing = elided.iter()
+                        .map(|&(_, ref snippet)| format!("`{}`", snippet))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+    report.note(
+        cause.span,
+        &format!("the lifetimes of {} were elided in the signature of the enclosing fn",
+                 listing));
+    for (span, snippet) in elided {
+        if snippet.starts_with("&") {
+            report.suggestion(span,
+                              "consider giving the elided lifetime a name",
+                              format!("&'a {}", snippet[1..].trim_left()));
+        }
+    }
+    // The fn's type parameters have to be repeated after the new lifetime.
+    let params: Vec<String> = Some("'a".to_string()).into_iter().chain(
+        generics.ty_params.iter().map(|param| param.name.to_string())).collect();
+    report.help(cause.span,
+                &format!("and declare it on the fn as `<{}>`, so that the required bound holds",
+                         params.join(", ")));
+}
+
+/// The messages a trait's `#[rustc_on_unimplemented]` attributes ask
+/// for, with the trait's type parameters substituted. A trait may carry
+/// several attributes, and each attribute several keys; all of their
+/// messages are kept, in the order they were written.
+struct OnUnimplementedMessages {
+    /// Replaces the primary message of the error, from the first
+    /// `message` key.
+    message: Option<String>,
+    /// Explanatory text, from the bare `= "..."` form or the `note` key.
+    notes: Vec<String>,
+    /// Guidance on fixing the error, from the `help` key.
+    helps: Vec<String>,
+    /// Short messages attached to the span of the error itself, from
+    /// the `label` key.
+    labels: Vec<String>,
+}
+
+impl OnUnimplementedMessages {
+    fn new() -> OnUnimplementedMessages {
+        OnUnimplementedMessages { message: None, notes: vec![], helps: vec![], labels: vec![] }
+    }
+}
+
+/// The messages for `trait_ref` from the trait's own attribute or, failing
+/// that, from its registered hook or the attribute of its first supertrait
+/// that has one. Errors in the attributes are added to `errors`.
+fn report_on_unimplemented<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                     trait_ref: &TraitRef<'tcx>,
+                                     cause: &ObligationCause<'tcx>,
+                                     errors: &mut Vec<TraitErrorReport>)
+                                     -> OnUnimplementedMessages {
+    let tcx = infcx.tcx;
+    if let Some(messages) = on_unimplemented_attribute(infcx, trait_ref, cause, errors) {
+        return messages;
+    }
+    let mut messages = OnUnimplementedMessages::new();
+    let hook = tcx.on_unimplemented_hooks.borrow().get(&trait_ref.def_id).cloned();
+    if let Some(hook) = hook {
+        messages.notes.extend(hook(tcx, trait_ref));
+        if !messages.notes.is_empty() {
+            return messages;
+        }
+    }
+
+    for supertrait_ref in supertraits(tcx, ty::Binder(*trait_ref)).skip(1) {
+        if let Some(inherited) = on_unimplemented_attribute(infcx, &supertrait_ref.0, cause,
+                                                            errors) {
+            let supertrait = tcx.item_path_str(supertrait_ref.def_id());
+            let attribute = |message: String| {
+                format!("{} (from the supertrait `{}`)", message, supertrait)
+            };
+            messages.message = inherited.message;
+            messages.notes.extend(inherited.notes.into_iter().map(&attribute));
+            messages.helps.extend(inherited.helps.into_iter().map(&attribute));
+            messages.labels.extend(inherited.labels.into_iter().map(&attribute));
+            break;
+        }
+    }
+    messages
+}
+
+/// The messages for `trait_ref` from the `#[rustc_on_unimplemented]`
+/// attributes of the trait itself, or `None` if it has none.
+fn on_unimplemented_attribute<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                        trait_ref: &TraitRef<'tcx>,
+                                        cause: &ObligationCause<'tcx>,
+                                        errors: &mut Vec<TraitErrorReport>)
+                                        -> Option<OnUnimplementedMessages> {
+    let def_id = trait_ref.def_id;
+    let mut messages = OnUnimplementedMessages::new();
+    let mut has_attribute = false;
+    for item in infcx.tcx.get_attrs(def_id).iter() {
+        if item.check_name("rustc_on_unimplemented") {
+            has_attribute = true;
+            let err_sp = item.meta().span.substitute_dummy(cause.span);
+            let trait_str = infcx.tcx.lookup_trait_def(def_id).trait_ref.to_string();
+            if let Some(ref istring) = item.value_str() {
+                messages.notes.extend(format_on_unimplemented(infcx, trait_ref, cause, istring,
+                                                              err_sp, &trait_str, errors));
+            } else if let Some(sub_items) = item.meta_item_list() {
+                // An attribute guarded by `on(...)` applies only when its
+                // guard holds, and then to the exclusion of all others;
+                // unguarded attributes are the fallback.
+                let guard = sub_items.iter().find(|sub_item| sub_item.check_name("on"));
+                let guarded = match guard {
+                    Some(guard) if !on_unimplemented_guard_holds(infcx, trait_ref, guard) => {
+                        continue;
+                    }
+                    Some(_) => true,
+                    None => false,
+                };
+                let mut entry = OnUnimplementedMessages::new();
+                // Malformed keys are reported when the trait is type checked.
+                for sub_item in sub_items {
+                    if let Some(ref istring) = sub_item.value_str() {
+                        let message = format_on_unimplemented(infcx, trait_ref, cause,
+                                                              istring, err_sp, &trait_str,
+                                                              errors);
+                        match &sub_item.name()[..] {
+                            "message" if entry.message.is_none() => entry.message = message,
+                            "note" => entry.notes.extend(message),
+                            "help" => entry.helps.extend(message),
+                            "label" => entry.labels.extend(message),
+                            _ => {}
+                        }
+                    }
+                }
+                if guarded {
+                    return Some(entry);
+                }
+                if messages.message.is_none() {
+                    messages.message = entry.message;
+                }
+                messages.notes.extend(entry.notes);
+                messages.helps.extend(entry.helps);
+                messages.labels.extend(entry.labels);
+            } else {
+                errors.push(trait_error_report!(false, err_sp, E0274,
+                                        "the #[rustc_on_unimplemented] attribute on \
+                                                 trait definition for {} must have a value, \
+                                                 eg `#[rustc_on_unimplemented = \"foo\"]`",
+                                                 trait_str));
+            }
+        }
+    }
+    if has_attribute {
+        Some(messages)
+    } else {
+        None
+    }
+}
+
+/// Checks the `on(T = "type", ...)` guard of a `#[rustc_on_unimplemented]`
+/// attribute: each named parameter must be the given type, where
+/// `{integer}` and `{float}` stand for any integer or float type.
+fn on_unimplemented_guard_holds<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                          trait_ref: &TraitRef<'tcx>,
+                                          guard: &ast::MetaItem) -> bool {
+    let params = on_unimplemented_params(infcx.tcx, trait_ref);
+    guard.meta_item_list().unwrap_or(&[]).iter().all(|condition| {
+        let (ty, expected) = match (params.get(&*condition.name()), condition.value_str()) {
+            (Some(&ty), Some(expected)) => (infcx.resolve_type_vars_if_possible(&ty), expected),
+            _ => return false,
+        };
+        match &expected[..] {
+            "{integer}" => ty.is_integral(),
+            "{float}" => ty.is_floating_point(),
+            expected => ty.to_string() == expected,
+        }
+    })
+}
+
+/// The types that `Self` and the trait's type parameters stand for in
+/// `trait_ref`, by name.
+fn on_unimplemented_params<'tcx>(tcx: &ty::ctxt<'tcx>,
+                                 trait_ref: &TraitRef<'tcx>)
+                                 -> FnvHashMap<String, Ty<'tcx>> {
+    let def = tcx.lookup_trait_def(trait_ref.def_id);
+    let mut params = def.generics.types.iter_enumerated()
+                        .map(|(param, i, gen)| {
+                              (gen.name.as_str().to_string(),
+                               *trait_ref.substs.types.get(param, i))
+                             }).collect::<FnvHashMap<String, Ty<'tcx>>>();
+    params.insert("Self".to_string(), trait_ref.self_ty());
+    params
+}
+
+/// The types that those of the trait's associated types named in `names`
+/// stand for in `trait_ref`, by name, with `_` for those that cannot be
+/// determined.
+fn on_unimplemented_associated_types<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                               trait_ref: &TraitRef<'tcx>,
+                                               cause: &ObligationCause<'tcx>,
+                                               names: &[&str])
+                                               -> Vec<(String, String)> {
+    let def = infcx.tcx.lookup_trait_def(trait_ref.def_id);
+    def.associated_type_names.iter().filter(|&&item_name| {
+        names.contains(&&*item_name.as_str())
+    }).map(|&item_name| {
+        let ty = infcx.probe(|_| {
+            let projection_ty = ty::ProjectionTy {
+                trait_ref: *trait_ref,
+                item_name: item_name,
+            };
+            let normalized = project::normalize_projection_type(
+                &mut SelectionContext::new(infcx),
+                projection_ty,
+                cause.clone(),
+                0);
+            let ty = infcx.resolve_type_vars_if_possible(&normalized.value);
+            if ty.needs_infer() || ty.references_error() {
+                "_".to_string()
+            } else {
+                ty.to_string()
+            }
+        });
+        (item_name.as_str().to_string(), ty)
+    }).collect()
+}
+
+/// Substitutes the trait's type parameters, associated types and `Self`
+/// into one of the strings of a `#[rustc_on_unimplemented]` attribute.
+/// Returns `None`, having added an error to `errors`, if the format
+/// string is malformed.
+fn format_on_unimplemented<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                     trait_ref: &TraitRef<'tcx>,
+                                     cause: &ObligationCause<'tcx>,
+                                     istring: &str,
+                                     err_sp: Span,
+                                     trait_str: &str,
+                                     errors: &mut Vec<TraitErrorReport>) -> Option<String> {
+    let mut parser = Parser::new(istring);
+    let pieces: Vec<Piece> = (&mut parser).collect();
+
+    let mut generic_map = on_unimplemented_params(infcx.tcx, trait_ref).into_iter()
+                              .map(|(name, ty)| (name, ty.to_string()))
+                              .collect::<FnvHashMap<String, String>>();
+    // Associated types are only normalized if the string refers to them.
+    let names: Vec<&str> = pieces.iter().filter_map(|piece| match *piece {
+        Piece::NextArgument(ref a) => match a.position {
+            Position::ArgumentNamed(s) if !generic_map.contains_key(s) => Some(s),
+            _ => None,
+        },
+        Piece::String(_) => None,
+    }).collect();
+    for (name, ty) in on_unimplemented_associated_types(infcx, trait_ref, cause, &names) {
+        generic_map.entry(name).or_insert(ty);
+    }
+    let mut errored = false;
+    let mut positional = 0;
+    let err: String = pieces.into_iter().filter_map(|p| {
+        match p {
+            // Escaped braces come through as literal text.
+            Piece::String(s) => Some(s),
+            Piece::NextArgument(a) => match a.position {
+                Position::ArgumentNamed(s) => match generic_map.get(s) {
+                    Some(val) => Some(val),
+                    None => {
+                        let mut error = trait_error_report!(false, err_sp, E0272,
+                                       "the #[rustc_on_unimplemented] \
+                                                attribute on \
+                                                trait definition for {} refers to \
+                                                non-existent type parameter {}",
+                                               trait_str, s);
+                        if let Some(name) = closest_name(generic_map.keys().map(|k| &k[..]), s) {
+                            error.help(err_sp, &format!("did you mean `{{{}}}`?", name));
+                        }
+                        errors.push(error);
+                        errored = true;
+                        None
+                    }
+                },
+                Position::ArgumentIs(i) => {
+                    let span = positional_argument_span(infcx.tcx.sess.codemap(), err_sp,
+                                                        istring, positional);
+                    positional += 1;
+                    errors.push(trait_error_report!(false, span, E0273,
+                                       "the #[rustc_on_unimplemented] attribute on \
+                                        trait definition for {} uses the positional \
+                                        argument `{{{}}}`; only type parameters can be \
+                                        substituted, by name, eg `{{Self}}`",
+                                       trait_str, i));
+                    errored = true;
+                    None
+                }
+                Position::ArgumentNext => {
+                    let span = positional_argument_span(infcx.tcx.sess.codemap(), err_sp,
+                                                        istring, positional);
+                    positional += 1;
+                    errors.push(trait_error_report!(false, span, E0273,
+                                       "the #[rustc_on_unimplemented] attribute on \
+                                        trait definition for {} must have named \
+                                        format arguments, \
+                                        eg `#[rustc_on_unimplemented = \"foo {{T}}\"]`",
+                                       trait_str));
+                    errored = true;
+                    None
+                }
+            }
+        }
+    }).collect();
+    // Report only if the format string checks out; a malformed one is
+    // reported when the trait is type checked.
+    if !errored && parser.errors.is_empty() {
+        Some(err)
+    } else {
+        None
+    }
+}
+
+/// The span of the `index`th positional argument (`{}`, `{0}`, `{:?}`,
+/// ...) of the format string `istring`, where it is written within
+/// `span`; or `span` itself, if `istring` is not written out verbatim
+/// there (because it contains escapes, say).
+pub fn positional_argument_span(codemap: &CodeMap,
+                                span: Span,
+                                istring: &str,
+                                index: usize)
+                                -> Span {
+    let offset = match codemap.span_to_snippet(span).ok().and_then(|s| s.find(istring)) {
+        Some(offset) => offset,
+        None => return span,
+    };
+    let bytes = istring.as_bytes();
+    let mut seen = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'{' {
+            i += 1;
+            continue;
+        }
+        if bytes.get(i + 1) == Some(&b'{') {
+            i += 2;
+            continue;
+        }
+        let end = match istring[i..].find('}') {
+            Some(len) => i + len + 1,
+            None => break,
+        };
+        let argument = istring[i + 1..end - 1].split(':').next().unwrap();
+        if argument.chars().all(|c| c.is_digit(10)) {
+            if seen == index {
+                return Span {
+                    lo: span.lo + BytePos((offset + i) as u32),
+                    hi: span.lo + BytePos((offset + end) as u32),
+                    expn_id: span.expn_id,
+                };
+            }
+            seen += 1;
+        }
+        i = end;
+    }
+    span
+}
+
+/// Reports that an overflow has occurred and halts compilation. We
+/// halt compilation unconditionally because it is important that
+/// overflows never be masked -- they basically represent computations
+/// whose result could not be truly determined and thus we can't say
+/// if the program type checks or not -- and they are unusual
+/// occurrences in any case. Callers that can fail the obligation
+/// instead use `report_overflow_error_recoverable`, which still makes
+/// compilation fail, but only once type checking is done.
+pub fn report_overflow_error<'a, 'tcx, T>(infcx: &InferCtxt<'a, 'tcx>,
+                                          obligation: &Obligation<'tcx, T>)
+                                          -> !
+    where T: fmt::Display + TypeFoldable<'tcx> + HasTypeFlags
+{
+    build_overflow_error_report(infcx, obligation).emit(infcx.tcx.sess);
+    infcx.tcx.sess.abort_if_errors();
+    unreachable!();
+}
+
+/// Like `report_overflow_error`, but returns so that the caller can fail
+/// the obligation with `SelectionError::Overflow` and other overflows
+/// can be reported in the same compilation. Each overflowing obligation
+/// is reported only once, however often selection runs into it.
+pub fn report_overflow_error_recoverable<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                                   obligation: &TraitObligation<'tcx>)
+{
+    let predicate = infcx.resolve_type_vars_if_possible(&obligation.predicate);
+    let error_key = TraitErrorKey {
+        is_warning: false,
+        span: obligation.cause.span,
+        predicate: infcx.tcx.erase_regions(&predicate.to_predicate()),
+    };
+    if !infcx.reported_trait_errors.borrow_mut().insert(error_key) {
+        return;
+    }
+    build_overflow_error_report(infcx, obligation).emit(infcx.tcx.sess);
+}
+
+/// Builds the report (E0275) for an overflow evaluating `obligation`.
+fn build_overflow_error_report<'a, 'tcx, T>(infcx: &InferCtxt<'a, 'tcx>,
+                                            obligation: &Obligation<'tcx, T>)
+                                            -> TraitErrorReport
+    where T: fmt::Display + TypeFoldable<'tcx> + HasTypeFlags
+{
+    let predicate =
+        infcx.resolve_type_vars_if_possible(&obligation.predicate);
+    let mut report = trait_error_report!(false, obligation.cause.span, E0275,
+                                         "overflow evaluating the requirement `{}`",
+                                         predicate);
+
+    suggest_new_overflow_limit(infcx.tcx, obligation.cause.span, &mut report);
+    note_overflow_chain(infcx, obligation, &mut report);
+
+    note_obligation_cause(infcx, obligation, &mut report);
+    report
+}
+
+/// The most requirements `note_overflow_chain` shows.
+const MAX_OVERFLOW_CHAIN: usize = 5;
+
+/// Shows the chain of requirements that led to an overflow, outermost
+/// first, so that genuine cycles can be told apart from computations that
+/// are merely deep. Only distinct requirements are shown, and only the
+/// innermost few of them.
+fn note_overflow_chain<'a, 'tcx, T>(infcx: &InferCtxt<'a, 'tcx>,
+                                    obligation: &Obligation<'tcx, T>,
+                                    report: &mut TraitErrorReport)
+    where T: fmt::Display + TypeFoldable<'tcx> + HasTypeFlags
+{
+    let mut frames = vec![infcx.resolve_type_vars_if_possible(&obligation.predicate).to_string()];
+    let mut cycle = false;
+    let mut truncated = false;
+    for trait_ref in derived_trait_refs(&obligation.cause.code) {
+        let frame = infcx.resolve_type_vars_if_possible(&trait_ref).to_predicate().to_string();
+        if frames.contains(&frame) {
+            frames.push(frame);
+            cycle = true;
+            break;
+        }
+        if frames.len() == MAX_OVERFLOW_CHAIN {
+            truncated = true;
+            break;
+        }
+        frames.push(frame);
+    }
+    if frames.len() < 2 {
+        return;
+    }
+
+    let chain = frames.iter().rev()
+                      .map(|frame| format!("`{}`", frame))
+                      .collect::<Vec<_>>()
+                      .join(" requires ");
+    let msg = if cycle {
+        format!("the requirements form a cycle: {}", chain)
+    } else if truncated {
+        format!("the requirement was reached through: ... {}", chain)
+    } else {
+        format!("the requirement was reached through: {}", chain)
+    };
+    report.note(
+        obligation.cause.span,
+        &msg);
+}
+
+/// When an operator trait is unimplemented for the operands of an
+/// operator expression, phrases the error in terms of the operator
+/// rather than the trait that desugars it.
+fn operator_error_message<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                    cause: &ObligationCause<'tcx>,
+                                    trait_ref: &ty::PolyTraitRef<'tcx>)
+                                    -> Option<String>
+{
+    let tcx = infcx.tcx;
+    let items = &tcx.lang_items;
+    let did = Some(trait_ref.def_id());
+    let is = |traits: &[Option<DefId>]| traits.contains(&did);
+    if !is(&[items.add_trait(), items.sub_trait(), items.mul_trait(), items.div_trait(),
+             items.rem_trait(), items.neg_trait(), items.not_trait(), items.bitxor_trait(),
+             items.bitand_trait(), items.bitor_trait(), items.shl_trait(), items.shr_trait(),
+             items.add_assign_trait(), items.sub_assign_trait(), items.mul_assign_trait(),
+             items.div_assign_trait(), items.rem_assign_trait(),
+             items.bitxor_assign_trait(), items.bitand_assign_trait(),
+             items.bitor_assign_trait(), items.shl_assign_trait(),
+             items.shr_assign_trait(), items.eq_trait(), items.ord_trait()]) {
+        return None;
+    }
+
+    let mut finder = OperatorFinder { span: cause.span, expr: None };
+    match tcx.map.find(enclosing_item_id(tcx, cause.body_id)) {
+        Some(ast_map::NodeItem(item)) => finder.visit_item(item),
+        Some(ast_map::NodeTraitItem(item)) => finder.visit_trait_item(item),
+        Some(ast_map::NodeImplItem(item)) => finder.visit_impl_item(item),
+        _ => return None,
+    }
+
+    let lhs = trait_ref.self_ty();
+    let rhs = trait_ref.0.substs.types.get_slice(subst::TypeSpace).first();
+    let expr = match finder.expr {
+        Some(expr) => expr,
+        None => return None,
+    };
+    match (&expr.node, rhs) {
+        (&hir::ExprUnary(op, _), _) if is(&[items.neg_trait(), items.not_trait()]) => {
+            Some(format!("cannot apply unary operator `{}` to type `{}`",
+                         hir_util::unop_to_string(op), lhs))
+        }
+        (&hir::ExprBinary(op, _, _), Some(rhs)) |
+        (&hir::ExprAssignOp(op, _, _), Some(rhs)) => {
+            if is(&[items.add_trait(), items.add_assign_trait()]) {
+                Some(format!("cannot add `{}` to `{}`", rhs, lhs))
+            } else if is(&[items.sub_trait(), items.sub_assign_trait()]) {
+                Some(format!("cannot subtract `{}` from `{}`", rhs, lhs))
+            } else if is(&[items.mul_trait(), items.mul_assign_trait()]) {
+                Some(format!("cannot multiply `{}` by `{}`", lhs, rhs))
+            } else if is(&[items.div_trait(), items.div_assign_trait()]) {
+                Some(format!("cannot divide `{}` by `{}`", lhs, rhs))
+            } else if is(&[items.rem_trait(), items.rem_assign_trait()]) {
+                Some(format!("cannot calculate the remainder of `{}` divided by `{}`",
+                             lhs, rhs))
+            } else if is(&[items.eq_trait(), items.ord_trait()]) {
+                Some(format!("cannot compare `{}` with `{}`", lhs, rhs))
+            } else if is(&[items.neg_trait(), items.not_trait()]) {
+                None
+            } else {
+                let assign = match expr.node {
+                    hir::ExprAssignOp(..) => "=",
+                    _ => "",
+                };
+                Some(format!("no implementation for `{} {}{} {}`",
+                             lhs, hir_util::binop_to_string(op.node), assign, rhs))
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Builds the report for a selection error, with every note and help it
+/// gets. Returns `None` if the error is not reported, or is reported
+/// some other way.
+///
+/// Nothing is emitted: errors in a `#[rustc_on_unimplemented]` attribute
+/// consulted along the way are kept in the report's `attribute_errors`.
+pub fn build_selection_error_report<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                              obligation: &PredicateObligation<'tcx>,
+                                              error: &SelectionError<'tcx>)
+                                              -> Option<TraitErrorReport>
+{
+    let mut report = match selection_error_message(infcx, obligation, error) {
+        Some(report) => report,
+        None => return None,
+    };
+    match *error {
+        SelectionError::Unimplemented => {
+            if let ObligationCauseCode::CompareImplMethodObligation(trait_method_def_id,
+                                                                    ref extra_predicates) =
+                    obligation.cause.code {
+                note_extra_impl_method_bounds(infcx, obligation.cause.span, extra_predicates,
+                                              &mut report);
+                note_trait_method_for_impl_bound(infcx.tcx,
+                                                 obligation.cause.span,
+                                                 trait_method_def_id,
+                                                 &obligation.predicate,
+                                                 &mut report);
+            } else {
+                match obligation.predicate {
+                    ty::Predicate::Trait(ref trait_predicate) => {
+                        let trait_predicate =
+                            infcx.resolve_type_vars_if_possible(trait_predicate);
+                        let trait_ref = trait_predicate.to_poly_trait_ref();
+                        let span = obligation.cause.span;
+                        let cause = &obligation.cause;
+                        note_similar_impls(infcx.tcx, span, &trait_ref, &mut report);
+                        note_same_path_trait_impls(infcx, cause, &trait_ref, &mut report);
+                        note_type_alias_expansions(infcx, cause, &trait_ref, &mut report);
+                        note_missing_object_traits(infcx, cause, &trait_ref, &mut report);
+                        note_unsized_associated_type(infcx.tcx, span, &trait_ref, &mut report);
+                        note_supertrait_object(infcx.tcx, span, &trait_ref, &mut report);
+                        note_orphan_rules_reservation(infcx.tcx, span, &trait_ref, &mut report);
+                        note_near_miss_blanket_impls(infcx, cause, &trait_ref, &mut report);
+                        if infcx.tcx.no_late_bound_regions(&trait_ref).is_none() {
+                            note_rejected_hr_instantiation_of(infcx, obligation, &mut report);
+                            note_higher_ranked_closure_args(infcx, span, &trait_ref,
+                                                            &mut report);
+                        }
+                        note_obligation_cause(infcx, obligation, &mut report);
+                        suggest_unsized_indirection(infcx.tcx, cause, &trait_ref, &mut report);
+                        suggest_clone_for_repeat(infcx, cause, &trait_ref, &mut report);
+                        suggest_borrow_or_deref(infcx, cause, &trait_ref, &mut report);
+                        suggest_bound_on_type_param(infcx.tcx, cause, &trait_ref, &mut report);
+                        suggest_overriding_default_type_param(infcx, cause, &trait_ref,
+                                                              &mut report);
+                    }
+
+                    ty::Predicate::Equate(..) => {
+                        note_obligation_cause(infcx, obligation, &mut report);
+                    }
+
+                    // `selection_error_message` has added the notes that
+                    // depend on why the regions are unrelated.
+                    ty::Predicate::RegionOutlives(..) => {
+                        note_obligation_cause(infcx, obligation, &mut report);
+                    }
+
+                    ty::Predicate::Projection(..) | ty::Predicate::TypeOutlives(..) => {
+                        let predicate =
+                            infcx.resolve_type_vars_if_possible(&obligation.predicate);
+                        if let ty::Predicate::TypeOutlives(ref data) = predicate {
+                            suggest_naming_elided_lifetimes(infcx, &obligation.cause,
+                                                            &[(data.0).1], &mut report);
+                        }
+                        note_obligation_cause(infcx, obligation, &mut report);
+                    }
+
+                    ty::Predicate::ObjectSafe(trait_def_id) => {
+                        note_methods_blocking_object_cast(infcx.tcx, obligation, trait_def_id,
+                                                          &mut report);
+                        note_obligation_cause(infcx, obligation, &mut report);
+                    }
+
+                    // `selection_error_message` does not return for these.
+                    ty::Predicate::WellFormed(..) => {}
+                }
+            }
+        }
+
+        OutputTypeParameterMismatch(_, ref actual_trait_ref, _) => {
+            let actual_trait_ref = infcx.resolve_type_vars_if_possible(&*actual_trait_ref);
+            note_higher_ranked_closure_args(infcx, obligation.cause.span, &actual_trait_ref,
+                                            &mut report);
+            note_obligation_cause(infcx, obligation, &mut report);
+        }
+
+        TraitNotObjectSafe(did) => {
+            note_methods_blocking_object_cast(infcx.tcx, obligation, did, &mut report);
+            note_obligation_cause(infcx, obligation, &mut report);
+        }
+
+        Overflow => {}
+    }
+    Some(report)
+}
+
+/// Starts the report for a selection error: its primary message, and
+/// the messages from the trait's `#[rustc_on_unimplemented]` attribute,
+/// which come before the notes worked out from the context. A failed
+/// `RegionOutlives` obligation also gets the notes on the region error
+/// here, as that error can only be computed once.
+fn selection_error_message<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                     obligation: &PredicateObligation<'tcx>,
+                                     error: &SelectionError<'tcx>)
+                                     -> Option<TraitErrorReport>
+{
+    let is_warning = is_warning(infcx.tcx.sess, obligation);
+    let span = obligation.cause.span;
+    match *error {
+        SelectionError::Unimplemented => {
+            if let ObligationCauseCode::CompareImplMethodObligation(..) = obligation.cause.code {
+                return Some(trait_error_report!(
+                    is_warning, span, E0276,
+                    "the requirement `{}` appears on the impl \
+                     method but not on the corresponding trait method",
+                    obligation.predicate));
+            }
+            match obligation.predicate {
+                ty::Predicate::Trait(ref trait_predicate) => {
+                    let trait_predicate = infcx.resolve_type_vars_if_possible(trait_predicate);
+                    if infcx.tcx.sess.has_errors() && trait_predicate.references_error() {
+                        return None;
+                    }
+                    let trait_ref = trait_predicate.to_poly_trait_ref();
+                    let named_trait_ref = with_source_param_names(
+                        infcx.tcx, obligation.cause.body_id, &trait_ref);
+                    let mut report = match operator_error_message(infcx, &obligation.cause,
+                                                                  &named_trait_ref) {
+                        Some(msg) => trait_error_report!(is_warning, span, E0277, "{}", msg),
+                        None => trait_error_report!(
+                            is_warning, span, E0277,
+                            "the trait `{}` is not implemented for the type `{}`",
+                            named_trait_ref, named_trait_ref.self_ty()),
+                    };
+
+                    // Check if it has a custom "#[rustc_on_unimplemented]"
+                    // error message, report with that message if it does
+                    let custom = report_on_unimplemented(infcx, &trait_ref.0, &obligation.cause,
+                                                         &mut report.attribute_errors);
+                    if let Some(message) = custom.message {
+                        report.message = message;
+                    }
+                    for s in custom.labels {
+                        report.children.push(TraitErrorChild::SpanNote(span, s));
+                    }
+                    for s in custom.notes {
+                        report.children.push(TraitErrorChild::Note(span, s));
+                    }
+                    for s in custom.helps {
+                        report.children.push(TraitErrorChild::Help(span, s));
+                    }
+                    Some(report)
+                }
+
+                ty::Predicate::Equate(ref predicate) => {
+                    let predicate = infcx.resolve_type_vars_if_possible(predicate);
+                    let err = infcx.equality_predicate(span, &predicate).err().unwrap();
+                    Some(trait_error_report!(
+                        is_warning, span, E0278,
+                        "the requirement `{}` is not satisfied (`{}`)",
+                        predicate,
+                        err))
+                }
+
+                ty::Predicate::RegionOutlives(ref predicate) => {
+                    // Relating the regions again has side effects on the
+                    // inference context, so everything that depends on
+                    // the error is worked out here.
+                    let predicate = infcx.resolve_type_vars_if_possible(predicate);
+                    let err = infcx.region_outlives_predicate(span, &predicate).err().unwrap();
+                    let mut report = trait_error_report!(
+                        is_warning, span, E0279,
+                        "the requirement `{}` is not satisfied (`{}`)",
+                        predicate,
+                        err);
+                    if let TypeError::RegionsInsufficientlyPolymorphic(br, region) = err {
+                        note_rejected_hr_instantiation(span, br, region, &mut report);
+                    }
+                    suggest_naming_elided_lifetimes(infcx, &obligation.cause,
+                                                    &[(predicate.0).0, (predicate.0).1],
+                                                    &mut report);
+                    Some(report)
+                }
+
+                ty::Predicate::Projection(..) | ty::Predicate::TypeOutlives(..) => {
+                    let predicate =
+                        infcx.resolve_type_vars_if_possible(&obligation.predicate);
+                    Some(trait_error_report!(
+                        is_warning, span, E0280,
+                        "the requirement `{}` is not satisfied",
+                        predicate))
+                }
+
+                ty::Predicate::ObjectSafe(trait_def_id) => {
+                    let violations = object_safety_violations(infcx.tcx, trait_def_id);
+                    Some(build_object_safety_error_report(infcx.tcx, span, trait_def_id,
+                                                          violations, is_warning))
+                }
+
+                ty::Predicate::WellFormed(ty) => {
+                    // WF predicates cannot themselves make
+                    // errors. They can only block due to
+                    // ambiguity; otherwise, they always
+                    // degenerate into other obligations
+                    // (which may fail).
+                    infcx.tcx.sess.span_bug(
+                        span,
+                        &format!("WF predicate not satisfied for {:?}", ty));
+                }
+            }
+        }
+
+        OutputTypeParameterMismatch(ref expected_trait_ref, ref actual_trait_ref, ref e) => {
+            let expected_trait_ref = infcx.resolve_type_vars_if_possible(&*expected_trait_ref);
+            let actual_trait_ref = infcx.resolve_type_vars_if_possible(&*actual_trait_ref);
+            if actual_trait_ref.self_ty().references_error() {
+                return None;
+            }
+            if let Some((callable, expected_args, actual_args)) =
+                    fn_trait_arguments(infcx.tcx, &expected_trait_ref, &actual_trait_ref) {
+                Some(trait_error_report!(
+                    is_warning, span, E0281,
+                    "type mismatch: {} takes arguments of type `{}`, \
+                     but the required trait `{}` expects `{}`",
+                    callable,
+                    expected_args,
+                    actual_trait_ref,
+                    actual_args))
+            } else {
+                Some(trait_error_report!(
+                    is_warning, span, E0281,
+                    "type mismatch: the type `{}` implements the trait `{}`, \
+                     but the trait `{}` is required ({})",
+                    expected_trait_ref.self_ty(),
+                    expected_trait_ref,
+                    actual_trait_ref,
+                    e))
+            }
+        }
+
+        TraitNotObjectSafe(did) => {
+            let violations = object_safety_violations(infcx.tcx, did);
+            Some(build_object_safety_error_report(infcx.tcx, span, did, violations, is_warning))
+        }
+
+        // Reported by `report_overflow_error_recoverable` when it happened.
+        Overflow => None,
+    }
+}
+
+pub fn report_selection_error<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                        obligation: &PredicateObligation<'tcx>,
+                                        error: &SelectionError<'tcx>)
+{
+    if let Some(report) = selection_error_report(infcx, obligation, error) {
+        report.emit(infcx.tcx.sess);
+    }
+}
+
+/// Builds the report for a selection error, and records the error for
+/// `-Z trait-error-summary`, or, for an object-safety error, so that the
+/// errors that follow from it are not reported.
+fn selection_error_report<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                    obligation: &PredicateObligation<'tcx>,
+                                    error: &SelectionError<'tcx>)
+                                    -> Option<TraitErrorReport>
+{
+    let report = build_selection_error_report(infcx, obligation, error);
+    if report.is_none() {
+        return None;
+    }
+    if let ObligationCauseCode::CompareImplMethodObligation(..) = obligation.cause.code {
+        return report;
+    }
+    match (error, &obligation.predicate) {
+        (&SelectionError::Unimplemented, &ty::Predicate::Trait(ref trait_predicate)) => {
+            let trait_predicate = infcx.resolve_type_vars_if_possible(trait_predicate);
+            record_unimplemented_trait(infcx.tcx, &trait_predicate.to_poly_trait_ref());
+        }
+        (&SelectionError::Unimplemented, &ty::Predicate::ObjectSafe(did)) |
+        (&TraitNotObjectSafe(did), _) => {
+            infcx.reported_object_safety_errors.borrow_mut()
+                 .insert((did, obligation.cause.span));
+        }
+        _ => {}
+    }
+    report
+}
+
+/// For a mismatch between two `Fn`-family trait references, describes
+/// the callable and returns the tupled argument types it takes and the
+/// ones the required trait expects.
+fn fn_trait_arguments<'tcx>(tcx: &ty::ctxt<'tcx>,
+                            expected_trait_ref: &ty::PolyTraitRef<'tcx>,
+                            actual_trait_ref: &ty::PolyTraitRef<'tcx>)
+                            -> Option<(&'static str, Ty<'tcx>, Ty<'tcx>)>
+{
+    if tcx.lang_items.fn_trait_kind(actual_trait_ref.def_id()).is_none() ||
+       tcx.lang_items.fn_trait_kind(expected_trait_ref.def_id()).is_none() {
+        return None;
+    }
+    let callable = match expected_trait_ref.self_ty().sty {
+        ty::TyClosure(..) => "the closure",
+        ty::TyBareFn(Some(_), _) => "the function",
+        ty::TyBareFn(None, _) => "the function pointer",
+        _ => return None,
+    };
+    match (expected_trait_ref.input_types().get(0), actual_trait_ref.input_types().get(0)) {
+        (Some(&expected_args), Some(&actual_args)) =>
+            Some((callable, expected_args, actual_args)),
+        _ => None,
+    }
+}
+
+/// Points at the trait method that an impl method's extra bound would
+/// have to be added to, and suggests adding it there.
+fn note_trait_method_for_impl_bound<T>(tcx: &ty::ctxt,
+                                       span: Span,
+                                       trait_method_def_id: DefId,
+                                       predicate: &T,
+                                       report: &mut TraitErrorReport)
+    where T: fmt::Display
+{
+    let def_span = tcx.map.def_id_span(trait_method_def_id, DUMMY_SP);
+    if def_span != DUMMY_SP {
+        report.span_note(def_span, "trait method declared here");
+    }
+    report.help(span,
+                &format!("consider adding `where {}` to the trait method `{}`, or removing \
+                          it from the impl method",
+                         predicate,
+                         tcx.item_path_str(trait_method_def_id)));
+}
+
+/// When the impl method has more than one bound that the trait method
+/// lacks, lists all of them so they can be fixed together.
+fn note_extra_impl_method_bounds<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                           span: Span,
+                                           extra_predicates: &[ty::Predicate<'tcx>],
+                                           report: &mut TraitErrorReport)
+{
+    if extra_predicates.len() < 2 {
+        return;
+    }
+    let listing = extra_predicates.iter()
+                                  .map(|p| format!("`{}`", infcx.resolve_type_vars_if_possible(p)))
+                                  .collect::<Vec<_>>()
+                                  .join(", ");
+    report.note(
+        span,
+        &format!("the impl method has these bounds that the trait method does not: {}",
+                 listing));
+}
+
+/// If some other crate defines a trait with the same path as the
+/// missing one and implements it for the self type -- typically because
+/// two versions of a dependency are linked in -- point that out, since
+/// that impl can never satisfy this bound. Only traits whose definitions
+/// have already been loaded are considered.
+fn note_same_path_trait_impls<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                        cause: &ObligationCause<'tcx>,
+                                        trait_ref: &ty::PolyTraitRef<'tcx>,
+                                        report: &mut TraitErrorReport)
+{
+    let tcx = infcx.tcx;
+    let trait_def_id = trait_ref.def_id();
+    if trait_ref.self_ty().needs_infer() {
+        return;
+    }
+
+    let path_of = |did: DefId| {
+        tcx.with_path(did, |p| p.map(|x| x.to_string()).collect::<Vec<_>>())
+    };
+    let trait_path = path_of(trait_def_id);
+    let num_params = |did: DefId| {
+        tcx.lookup_trait_def(did).generics.types.len(subst::TypeSpace)
+    };
+
+    // We compare paths as strings, as in `check_and_note_conflicting_crates`.
+    let candidates: Vec<DefId> =
+        tcx.trait_defs.borrow().keys()
+                              .cloned()
+                              .filter(|&did| did.krate != trait_def_id.krate)
+                              .collect();
+    for did in candidates {
+        if path_of(did) != trait_path || num_params(did) != num_params(trait_def_id) {
+            continue;
+        }
+        let other_trait_ref = ty::TraitRef::new(did, trait_ref.0.substs);
+        if trait_ref_may_hold(infcx, cause, other_trait_ref) {
+            let crate_name = if did.is_local() {
+                "this crate".to_string()
+            } else {
+                format!("crate `{}`", tcx.sess.cstore.get_crate_data(did.krate).name())
+            };
+            report.note(
+                cause.span,
+                &format!("`{}` is implemented for `{}` by the trait of the same name \
+                          in {}, which is a different trait; perhaps two different \
+                          versions of that crate are being used?",
+                         tcx.item_path_str(trait_def_id),
+                         trait_ref.self_ty(),
+                         crate_name));
+        }
+    }
+}
+
+/// The most impls `note_similar_impls` lists before summarizing the rest.
+const MAX_SIMILAR_IMPLS: usize = 4;
+
+/// Lists the impls of the unimplemented trait whose self type is of the
+/// same sort as the one that was wanted (another integer type, another
+/// instantiation of the same struct, ...), since one of them is often
+/// what the user meant.
+fn note_similar_impls<'tcx>(tcx: &ty::ctxt<'tcx>,
+                            span: Span,
+                            trait_ref: &ty::PolyTraitRef<'tcx>,
+                            report: &mut TraitErrorReport)
+{
+    let self_ty = trait_ref.self_ty();
+    if self_ty.references_error() {
+        return;
+    }
+    let self_sort = match type_sort(self_ty) {
+        Some(sort) => sort,
+        None => return,
+    };
+
+    let mut impls = vec![];
+    tcx.lookup_trait_def(trait_ref.def_id()).for_each_impl(tcx, |impl_def_id| {
+        if let Some(impl_trait_ref) = tcx.impl_trait_ref(impl_def_id) {
+            if type_sort(impl_trait_ref.self_ty()) == Some(self_sort) {
+                impls.push(format!("<{} as {}>", impl_trait_ref.self_ty(), impl_trait_ref));
+            }
+        }
+    });
+    if impls.is_empty() {
+        return;
+    }
+    impls.sort();
+
+    report.note(
+        span,
+        "the following implementations were found:");
+    for candidate in impls.iter().take(MAX_SIMILAR_IMPLS) {
+        report.note(
+            span,
+            &format!("  {}", candidate));
+    }
+    if impls.len() > MAX_SIMILAR_IMPLS {
+        report.note(
+            span,
+            &format!("and {} others", impls.len() - MAX_SIMILAR_IMPLS));
+    }
+}
+
+/// Points out blanket impls like `impl<T: Bound> Trait for T` that match
+/// the unimplemented trait reference except for some of their bounds.
+fn note_near_miss_blanket_impls<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                          cause: &ObligationCause<'tcx>,
+                                          trait_ref: &ty::PolyTraitRef<'tcx>,
+                                          report: &mut TraitErrorReport)
+{
+    let tcx = infcx.tcx;
+    let self_ty = match tcx.no_late_bound_regions(trait_ref) {
+        Some(trait_ref) => trait_ref.self_ty(),
+        None => return,
+    };
+    if self_ty.needs_infer() {
+        return;
+    }
+    let trait_obligation = Obligation::new(cause.clone(), trait_ref.to_poly_trait_predicate());
+
+    let mut near_misses = vec![];
+    tcx.lookup_trait_def(trait_ref.def_id()).for_each_relevant_impl(tcx, self_ty, |impl_def_id| {
+        let impl_trait_ref = match tcx.impl_trait_ref(impl_def_id) {
+            Some(impl_trait_ref) => impl_trait_ref,
+            None => return,
+        };
+        let param = match impl_trait_ref.self_ty().sty {
+            ty::TyParam(ref param) => param.clone(),
+            _ => return,
+        };
+        if !SelectionContext::new(infcx).match_impl_header(impl_def_id, &trait_obligation) {
+            return;
+        }
+
+        let replace_param = |replacement: Ty<'tcx>| BottomUpFolder {
+            tcx: tcx,
+            fldop: move |ty: Ty<'tcx>| match ty.sty {
+                ty::TyParam(ref p) if p.space == param.space && p.idx == param.idx => replacement,
+                _ => ty,
+            },
+        };
+        let mut unsatisfied = vec![];
+        for predicate in &tcx.lookup_predicates(impl_def_id).predicates {
+            let bound = match *predicate {
+                ty::Predicate::Trait(ref data) => &data.0.trait_ref,
+                _ => continue,
+            };
+            // Only bounds on the blanket parameter alone can be checked
+            // without knowing the impl's other parameters.
+            if bound.self_ty() != impl_trait_ref.self_ty() ||
+               bound.fold_with(&mut replace_param(tcx.types.err)).needs_subst() {
+                continue;
+            }
+            let instantiated = bound.fold_with(&mut replace_param(self_ty));
+            if !trait_ref_may_hold(infcx, cause, instantiated) {
+                unsatisfied.push((bound.clone(), instantiated));
+            }
+        }
+        if !unsatisfied.is_empty() {
+            near_misses.push((impl_def_id, impl_trait_ref, unsatisfied));
+        }
+    });
+
+    for (impl_def_id, impl_trait_ref, unsatisfied) in near_misses {
+        let bounds = |refs: Vec<String>| refs.join(", ");
+        let msg = format!(
+            "the trait `{}` is implemented for `{}` where {}, but {} {} not satisfied",
+            impl_trait_ref,
+            impl_trait_ref.self_ty(),
+            bounds(unsatisfied.iter().map(|&(ref bound, _)| {
+                format!("`{}: {}`", bound.self_ty(), bound)
+            }).collect()),
+            bounds(unsatisfied.iter().map(|&(_, ref instantiated)| {
+                format!("`{}: {}`", instantiated.self_ty(), instantiated)
+            }).collect()),
+            if unsatisfied.len() == 1 { "is" } else { "are" });
+        match tcx.map.as_local_node_id(impl_def_id) {
+            Some(node_id) => report.span_note(tcx.map.span(node_id), &msg),
+            None => report.note(cause.span, &msg),
+        }
+    }
+}
+
+/// A coarse classification of types, used to decide which impls are
+/// close enough to a wanted self type to be worth listing. Type
+/// parameters and inference variables have no sort.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum TypeSort {
+    Bool,
+    Char,
+    Integer,
+    Float,
+    Str,
+    Adt(DefId),
+    Pointer,
+    Array,
+    Tuple,
+    Function,
+    Object,
+}
+
+fn type_sort(ty: Ty) -> Option<TypeSort> {
+    Some(match ty.sty {
+        ty::TyBool => TypeSort::Bool,
+        ty::TyChar => TypeSort::Char,
+        ty::TyInt(..) | ty::TyUint(..) | ty::TyInfer(ty::IntVar(_)) => TypeSort::Integer,
+        ty::TyFloat(..) | ty::TyInfer(ty::FloatVar(_)) => TypeSort::Float,
+        ty::TyStr => TypeSort::Str,
+        ty::TyEnum(def, _) | ty::TyStruct(def, _) => TypeSort::Adt(def.did),
+        ty::TyBox(..) | ty::TyRef(..) | ty::TyRawPtr(..) => TypeSort::Pointer,
+        ty::TyArray(..) | ty::TySlice(..) => TypeSort::Array,
+        ty::TyTuple(..) => TypeSort::Tuple,
+        ty::TyBareFn(..) | ty::TyClosure(..) => TypeSort::Function,
+        ty::TyTrait(..) => TypeSort::Object,
+        ty::TyParam(..) | ty::TyProjection(..) | ty::TyInfer(..) | ty::TyError => return None,
+    })
+}
+
+/// A `Super` trait object does not implement `Sub` just because `Sub:
+/// Super`: the object may hold a type that implements only `Super`, and
+/// there is no converting it into a `Sub` object.
+fn note_supertrait_object<'tcx>(tcx: &ty::ctxt<'tcx>,
+                                span: Span,
+                                trait_ref: &ty::PolyTraitRef<'tcx>,
+                                report: &mut TraitErrorReport)
+{
+    let object_def_id = match trait_ref.self_ty().sty {
+        ty::TyTrait(ref data) => data.principal_def_id(),
+        _ => return,
+    };
+    if object_def_id == trait_ref.def_id() ||
+       !supertrait_def_ids(tcx, trait_ref.def_id()).any(|did| did == object_def_id) {
+        return;
+    }
+    report.note(
+        span,
+        &format!("`{sup}` is a supertrait of `{sub}`, but a `{sup}` object cannot be turned \
+                  into a `{sub}` object; consider holding a `{sub}` object from the start",
+                 sup = tcx.item_path_str(object_def_id),
+                 sub = tcx.item_path_str(trait_ref.def_id())));
+}
+
+/// Users who hit a missing impl of an upstream trait for an upstream
+/// type are often puzzled that they cannot simply write it. Explain that
+/// coherence reserves that impl for the crates defining the two.
+fn note_orphan_rules_reservation<'tcx>(tcx: &ty::ctxt<'tcx>,
+                                       span: Span,
+                                       trait_ref: &ty::PolyTraitRef<'tcx>,
+                                       report: &mut TraitErrorReport)
+{
+    let trait_ref = tcx.erase_late_bound_regions(trait_ref);
+    if trait_ref.has_param_types() || trait_ref.has_infer_types() ||
+       coherence::local_impl_allowed(tcx, &trait_ref) {
+        return;
+    }
+    report.note(
+        span,
+        &format!("`impl {} for {}` cannot be written in this crate: coherence \
+                  reserves it for the crates that define the trait or the type",
+                 trait_ref,
+                 trait_ref.self_ty()));
+}
+
+/// Remembers that `trait_ref` was reported as unimplemented, for the
+/// summary printed by `report_unimplemented_trait_summary`.
+fn record_unimplemented_trait<'tcx>(tcx: &ty::ctxt<'tcx>, trait_ref: &ty::PolyTraitRef<'tcx>) {
+    if !tcx.sess.opts.debugging_opts.trait_error_summary {
+        return;
+    }
+    let pair = (trait_ref.self_ty().to_string(), tcx.item_path_str(trait_ref.def_id()));
+    let mut unimplemented = tcx.unimplemented_traits.borrow_mut();
+    if !unimplemented.contains(&pair) {
+        unimplemented.push(pair);
+    }
+}
+
+/// Under `-Z trait-error-summary`, prints every distinct `(self type,
+/// trait)` pair reported as unimplemented since the last call, as a
+/// to-do list of the impls that are missing.
+pub fn report_unimplemented_trait_summary(tcx: &ty::ctxt) {
+    let unimplemented = mem::replace(&mut *tcx.unimplemented_traits.borrow_mut(), Vec::new());
+    if unimplemented.is_empty() {
+        return;
+    }
+    tcx.sess.note(&format!("{} distinct trait{} not implemented:",
+                           unimplemented.len(),
+                           if unimplemented.len() == 1 { " was" } else { "s were" }));
+    for (self_ty, trait_name) in unimplemented {
+        tcx.sess.note(&format!("    `{}` for `{}`", trait_name, self_ty));
+    }
+}
+
+/// The self type of a failed bound is printed fully expanded, so if the
+/// user wrote it through a type alias they may not recognize it. For
+/// each (non-generic) alias named in the enclosing item that expands to
+/// the self type, show the expansion.
+fn note_type_alias_expansions<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                        cause: &ObligationCause<'tcx>,
+                                        trait_ref: &ty::PolyTraitRef<'tcx>,
+                                        report: &mut TraitErrorReport)
+{
+    let tcx = infcx.tcx;
+    let self_ty = tcx.erase_regions(&trait_ref.self_ty());
+    let mut finder = TypeAliasFinder { tcx: tcx, aliases: vec![] };
+    match tcx.map.find(enclosing_item_id(tcx, cause.body_id)) {
+        Some(ast_map::NodeItem(item)) => finder.visit_item(item),
+        Some(ast_map::NodeTraitItem(item)) => finder.visit_trait_item(item),
+        Some(ast_map::NodeImplItem(item)) => finder.visit_impl_item(item),
+        _ => return,
+    }
+
+    let mut reported = FnvHashSet();
+    for (alias_def_id, span) in finder.aliases {
+        let scheme = tcx.lookup_item_type(alias_def_id);
+        if !scheme.generics.types.is_empty() || !reported.insert(alias_def_id) {
+            continue;
+        }
+        if tcx.erase_regions(&scheme.ty) == self_ty {
+            report.span_note(
+                span,
+                &format!("`{}` expands to `{}`, which does not implement `{}`",
+                         tcx.item_path_str(alias_def_id),
+                         scheme.ty,
+                         tcx.item_path_str(trait_ref.def_id())));
+        }
+    }
+}
+
+/// Collects every type alias named in a type, with the span where it
+/// was written.
+struct TypeAliasFinder<'a, 'tcx: 'a> {
+    tcx: &'a ty::ctxt<'tcx>,
+    aliases: Vec<(DefId, Span)>,
+}
+
+impl<'a, 'tcx, 'v> Visitor<'v> for TypeAliasFinder<'a, 'tcx> {
+    fn visit_ty(&mut self, t: &'v hir::Ty) {
+        if let hir::TyPath(None, _) = t.node {
+            // `DefTy(_, false)` is a type alias; enums have `true`.
+            if let Some(&def::PathResolution { base_def: def::DefTy(did, false),
+                                                depth: 0, .. }) =
+                    self.tcx.def_map.borrow().get(&t.id) {
+                self.aliases.push((did, t.span));
+            }
+        }
+        visit::walk_ty(self, t);
+    }
+}
+
+/// For `[x; N]` whose element type is `Clone` but not `Copy`, suggests
+/// constructions that clone the element instead. `Clone` is found as
+/// the supertrait of `Copy`, and the probe is skipped if it can't be.
+fn suggest_clone_for_repeat<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                      cause: &ObligationCause<'tcx>,
+                                      trait_ref: &ty::PolyTraitRef<'tcx>,
+                                      report: &mut TraitErrorReport)
+{
+    let tcx = infcx.tcx;
+    let copy_def_id = match tcx.lang_items.copy_trait() {
+        Some(def_id) if def_id == trait_ref.def_id() => def_id,
+        _ => return,
+    };
+    let code = unwrapped_cause_code(&cause.code);
+    match *code {
+        ObligationCauseCode::RepeatVec => {}
+        _ => return,
+    }
+    let self_ty = trait_ref.self_ty();
+    if self_ty.references_error() || self_ty.needs_infer() {
+        return;
+    }
+    let clone_def_id = match supertrait_def_ids(tcx, copy_def_id).find(|&id| id != copy_def_id) {
+        Some(def_id) => def_id,
+        None => return,
+    };
+    let clone_ref = ty::TraitRef::new(clone_def_id, trait_ref.0.substs);
+    if !trait_ref_may_hold(infcx, cause, clone_ref) {
+        return;
+    }
+    report.help(cause.span,
+                &format!("`{}` implements `{}` but not `Copy`, and `[x; N]` copies the element; \
+                          consider `vec![x; N]`, which clones it, or filling the array \
+                          in an explicit loop",
+                         self_ty,
+                         tcx.item_path_str(clone_def_id)));
+}
+
+/// When the bound required of an expression's type `T` does not hold,
+/// but would for `&T`, suggests borrowing; when it does not hold for
+/// `&T` but would for `T`, suggests dereferencing. For a derived
+/// obligation, the bound probed is the outermost one, whose self type
+/// is that of the expression.
+fn suggest_borrow_or_deref<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                     cause: &ObligationCause<'tcx>,
+                                     trait_ref: &ty::PolyTraitRef<'tcx>,
+                                     report: &mut TraitErrorReport)
+{
+    let tcx = infcx.tcx;
+    let trait_ref = match derived_trait_refs(&cause.code).pop() {
+        Some(parent_trait_ref) => infcx.resolve_type_vars_if_possible(&parent_trait_ref),
+        None => trait_ref.clone(),
+    };
+    if tcx.lang_items.sized_trait() == Some(trait_ref.def_id()) {
+        return;
+    }
+    let self_ty = trait_ref.self_ty();
+    if self_ty.references_error() || self_ty.needs_infer() {
+        return;
+    }
+    let trait_ref = match tcx.no_late_bound_regions(&trait_ref) {
+        Some(trait_ref) => trait_ref,
+        None => return,
+    };
+    let (adjusted_ty, shown_ty, action) = match self_ty.sty {
+        ty::TyRef(_, ty::TypeAndMut { ty, .. }) => (ty, ty.to_string(), "dereferencing"),
+        _ => {
+            let borrowed_ty = tcx.mk_imm_ref(tcx.mk_region(ty::ReStatic), self_ty);
+            (borrowed_ty, format!("&{}", self_ty), "borrowing")
+        }
+    };
+    let mut substs = trait_ref.substs.clone();
+    substs.types.get_mut_slice(subst::SelfSpace)[0] = adjusted_ty;
+    let adjusted_ref = ty::TraitRef::new(trait_ref.def_id, tcx.mk_substs(substs));
+    if !trait_ref_may_hold(infcx, cause, adjusted_ref) {
+        return;
+    }
+    report.help(cause.span,
+                &format!("the trait `{}` is implemented for `{}`; consider {} here",
+                         trait_ref, shown_ty, action));
+}
+
+/// Follows up the note for a `Sized` bound on a local variable or a
+/// struct field with the ways to satisfy, or avoid, it.
+fn suggest_unsized_indirection<'tcx>(tcx: &ty::ctxt<'tcx>,
+                                     cause: &ObligationCause<'tcx>,
+                                     trait_ref: &ty::PolyTraitRef<'tcx>,
+                                     report: &mut TraitErrorReport)
+{
+    if tcx.lang_items.sized_trait() != Some(trait_ref.def_id()) {
+        return;
+    }
+    let self_ty = trait_ref.self_ty();
+    if self_ty.references_error() {
+        return;
+    }
+    let code = unwrapped_cause_code(&cause.code);
+    let what = match *code {
+        ObligationCauseCode::VariableType(_) => "the variable",
+        ObligationCauseCode::FieldSized => "the field",
+        _ => return,
+    };
+    let indirection = format!("storing {} behind a `Box<{}>` or `&{}`", what, self_ty, self_ty);
+    let msg = match self_ty.sty {
+        ty::TyParam(_) => format!("consider removing the `?Sized` bound on `{}`, or {}",
+                                  self_ty, indirection),
+        _ => format!("consider {}", indirection),
+    };
+    let msg = match *code {
+        ObligationCauseCode::FieldSized => {
+            format!("{}; only the last field may be unsized, so moving the field to \
+                     the end is another option", msg)
+        }
+        _ => msg,
+    };
+    report.help(cause.span, &msg);
+}
+
+/// Associated types are `Sized` unless declared `?Sized`. When a `Sized`
+/// bound fails for a projection whose associated type opted out, say so
+/// and show how to require it where it is needed.
+fn note_unsized_associated_type<'tcx>(tcx: &ty::ctxt<'tcx>,
+                                      span: Span,
+                                      trait_ref: &ty::PolyTraitRef<'tcx>,
+                                      report: &mut TraitErrorReport)
+{
+    if tcx.lang_items.sized_trait() != Some(trait_ref.def_id()) {
+        return;
+    }
+    let projection = match trait_ref.self_ty().sty {
+        ty::TyProjection(ref projection) => projection,
+        _ => return,
+    };
+    let trait_def_id = projection.trait_ref.def_id;
+    let declared_sized = tcx.lookup_predicates(trait_def_id).predicates.iter().any(|p| {
+        match *p {
+            ty::Predicate::Trait(ref data) => {
+                data.def_id() == trait_ref.def_id() &&
+                match data.0.self_ty().sty {
+                    ty::TyProjection(ref p) => p.item_name == projection.item_name,
+                    _ => false,
+                }
+            }
+            _ => false,
+        }
+    });
+    if declared_sized {
+        return;
+    }
+    report.note(
+        span,
+        &format!("the associated type `{}::{}` is declared `?Sized`, so `{}` \
+                  is not known to be `Sized`",
+                 tcx.item_path_str(trait_def_id),
+                 projection.item_name,
+                 trait_ref.self_ty()));
+    report.help(
+        span,
+        &format!("consider adding a `where {}: Sized` bound",
+                 trait_ref.self_ty()));
+}
+
+/// When a cast to an object type with several traits, like `Foo + Send`,
+/// fails, says which of the listed traits the type is missing and
+/// points at where they are listed.
+fn note_missing_object_traits<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                        cause: &ObligationCause<'tcx>,
+                                        trait_ref: &ty::PolyTraitRef<'tcx>,
+                                        report: &mut TraitErrorReport)
+{
+    let tcx = infcx.tcx;
+    let object_ty = match cause.code {
+        ObligationCauseCode::ObjectCastObligation(object_ty) => object_ty,
+        _ => return,
+    };
+    let data = match object_ty.sty {
+        ty::TyTrait(ref data) => data,
+        _ => return,
+    };
+    let self_ty = trait_ref.self_ty();
+    if self_ty.has_escaping_regions() || data.bounds.builtin_bounds.is_empty() {
+        return;
+    }
+
+    let mut listed = vec![*data.principal_trait_ref_with_self_ty(tcx, self_ty).skip_binder()];
+    for bound in &data.bounds.builtin_bounds {
+        if let Ok(bound_ref) = trait_ref_for_builtin_bound(tcx, bound, self_ty) {
+            listed.push(bound_ref);
+        }
+    }
+    let missing = listed.iter()
+                        .filter(|r| !trait_ref_may_hold(infcx, cause, **r))
+                        .map(|r| r.def_id)
+                        .collect::<Vec<_>>();
+    if missing.is_empty() {
+        return;
+    }
+    let names = |def_ids: &[DefId]| {
+        def_ids.iter()
+               .map(|&did| format!("`{}`", tcx.item_path_str(did)))
+               .collect::<Vec<_>>()
+               .join(", ")
+    };
+    report.note(
+        cause.span,
+        &format!("the object type `{}` requires each of {}, but `{}` does not implement {}",
+                 object_ty,
+                 names(&listed.iter().map(|r| r.def_id).collect::<Vec<_>>()),
+                 self_ty,
+                 names(&missing)));
+
+    let mut finder = ObjectTraitFinder { tcx: tcx, listed: vec![] };
+    match tcx.map.find(enclosing_item_id(tcx, cause.body_id)) {
+        Some(ast_map::NodeItem(item)) => finder.visit_item(item),
+        Some(ast_map::NodeTraitItem(item)) => finder.visit_trait_item(item),
+        Some(ast_map::NodeImplItem(item)) => finder.visit_impl_item(item),
+        _ => return,
+    }
+    let mut reported = FnvHashSet();
+    for (did, span) in finder.listed {
+        if missing.contains(&did) && reported.insert(did) {
+            report.span_note(
+                span,
+                &format!("`{}` is listed in the object type here",
+                         tcx.item_path_str(did)));
+        }
+    }
+}
+
+/// Collects every trait listed in an object type (`Foo + Send`), with
+/// the span where it was written.
+struct ObjectTraitFinder<'a, 'tcx: 'a> {
+    tcx: &'a ty::ctxt<'tcx>,
+    listed: Vec<(DefId, Span)>,
+}
+
+impl<'a, 'tcx> ObjectTraitFinder<'a, 'tcx> {
+    fn record(&mut self, id: ast::NodeId, span: Span) {
+        if let Some(&def::PathResolution { base_def: def::DefTrait(did), depth: 0, .. }) =
+                self.tcx.def_map.borrow().get(&id) {
+            self.listed.push((did, span));
+        }
+    }
+
+    fn record_bounds(&mut self, bounds: &hir::TyParamBounds) {
+        for bound in bounds.iter() {
+            if let hir::TraitTyParamBound(ref poly_trait_ref, _) = *bound {
+                self.record(poly_trait_ref.trait_ref.ref_id, poly_trait_ref.span);
+            }
+        }
+    }
+}
+
+impl<'a, 'tcx, 'v> Visitor<'v> for ObjectTraitFinder<'a, 'tcx> {
+    fn visit_ty(&mut self, t: &'v hir::Ty) {
+        match t.node {
+            hir::TyObjectSum(ref principal, ref bounds) => {
+                self.record(principal.id, principal.span);
+                self.record_bounds(bounds);
+            }
+            hir::TyPolyTraitRef(ref bounds) => self.record_bounds(bounds),
+            _ => {}
+        }
+        visit::walk_ty(self, t);
+    }
+}
+
+/// Re-runs selection for a failed higher-ranked obligation to find the
+/// instantiation of its bound lifetimes that the leak check rejected,
+/// and reports it as a counterexample.
+fn note_rejected_hr_instantiation_of<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                               obligation: &PredicateObligation<'tcx>,
+                                               report: &mut TraitErrorReport)
+{
+    let rejected = infcx.probe(|_| {
+        infcx.last_leak_check_failure.set(None);
+        SelectionContext::new(infcx).evaluate_obligation(obligation);
+        infcx.last_leak_check_failure.get()
+    });
+    if let Some((br, region)) = rejected {
+        note_rejected_hr_instantiation(obligation.cause.span, br, region, report);
+    }
+}
 
-                    ty::Predicate::WellFormed(ty) => {
-                        // WF predicates cannot themselves make
-                        // errors. They can only block due to
-                        // ambiguity; otherwise, they always
-                        // degenerate into other obligations
-                        // (which may fail).
-                        infcx.tcx.sess.span_bug(
-                            obligation.cause.span,
-                            &format!("WF predicate not satisfied for {:?}", ty));
-                    }
-                }
-            }
-        }
+/// A higher-ranked bound `for<'a> ...` must hold for every `'a`; say
+/// which `'a` it was found not to hold for.
+fn note_rejected_hr_instantiation(span: Span,
+                                  br: ty::BoundRegion,
+                                  region: ty::Region,
+                                  report: &mut TraitErrorReport)
+{
+    let bound = match br {
+        ty::BrNamed(_, name) => format!("`{}`", name),
+        _ => "the bound lifetime".to_string(),
+    };
+    let instantiation = match region.to_string() {
+        ref s if s.is_empty() => "a lifetime local to this code".to_string(),
+        s => format!("`{}`", s),
+    };
+    report.note(
+        span,
+        &format!("this bound is required for all lifetimes, but fails when \
+                  {} is {}",
+                 bound,
+                 instantiation));
+}
 
-        OutputTypeParameterMismatch(ref expected_trait_ref, ref actual_trait_ref, ref e) => {
-            let expected_trait_ref = infcx.resolve_type_vars_if_possible(&*expected_trait_ref);
-            let actual_trait_ref = infcx.resolve_type_vars_if_possible(&*actual_trait_ref);
-            if !actual_trait_ref.self_ty().references_error() {
-                span_err_or_warn!(
-                    is_warning, infcx.tcx.sess, obligation.cause.span, E0281,
-                    "type mismatch: the type `{}` implements the trait `{}`, \
-                     but the trait `{}` is required ({})",
-                    expected_trait_ref.self_ty(),
-                    expected_trait_ref,
-                    actual_trait_ref,
-                    e);
-                note_obligation_cause(infcx, obligation);
-            }
+/// When a closure fails a higher-ranked `Fn`-family bound such as
+/// `for<'a> Fn(&'a T)`, says which of its arguments must accept every
+/// lifetime the bound quantifies over.
+fn note_higher_ranked_closure_args<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                             span: Span,
+                                             trait_ref: &ty::PolyTraitRef<'tcx>,
+                                             report: &mut TraitErrorReport)
+{
+    let tcx = infcx.tcx;
+    if tcx.lang_items.fn_trait_kind(trait_ref.def_id()).is_none() {
+        return;
+    }
+    let closure_def_id = match trait_ref.self_ty().sty {
+        ty::TyClosure(def_id, _) => def_id,
+        _ => return,
+    };
+    let arg_tys = match trait_ref.input_types().get(0).map(|ty| &ty.sty) {
+        Some(&ty::TyTuple(ref arg_tys)) => arg_tys,
+        _ => return,
+    };
+    let arg_pats = match tcx.map.as_local_node_id(closure_def_id).and_then(|id| tcx.map.find(id)) {
+        Some(ast_map::NodeExpr(&hir::Expr { node: hir::ExprClosure(_, ref decl, _), .. })) => {
+            decl.inputs.iter().map(|arg| pprust::pat_to_string(&arg.pat)).collect()
         }
+        _ => vec![],
+    };
 
-        TraitNotObjectSafe(did) => {
-            let violations = object_safety_violations(infcx.tcx, did);
-            report_object_safety_error(infcx.tcx, obligation.cause.span, did,
-                                       violations, is_warning);
-            note_obligation_cause(infcx, obligation);
+    for (index, &arg_ty) in arg_tys.iter().enumerate() {
+        let (_, bound_regions) = tcx.replace_late_bound_regions(&ty::Binder(arg_ty), |br| {
+            ty::ReLateBound(ty::DebruijnIndex::new(1), br)
+        });
+        if bound_regions.is_empty() {
+            continue;
         }
+        let mut lifetimes = bound_regions.keys().map(|br| match *br {
+            ty::BrNamed(_, name) => format!("`{}`", name),
+            _ => "anonymous lifetime".to_string(),
+        }).collect::<Vec<_>>();
+        lifetimes.sort();
+        lifetimes.dedup();
+        let arg = match arg_pats.get(index) {
+            Some(pat) => format!("argument `{}`", pat),
+            None => format!("argument #{}", index + 1),
+        };
+        report.note(
+            span,
+            &format!("the closure's {} must have type `{}` for every {}{}, as required by `{}`",
+                     arg,
+                     arg_ty,
+                     if lifetimes.len() == 1 { "" } else { "choice of " },
+                     lifetimes.join(", "),
+                     trait_ref));
     }
 }
 
+/// Best-effort check of whether `trait_ref` may hold, leaving the
+/// inference state untouched. Used to probe for alternatives when
+/// reporting an error, so an ambiguous answer counts as success.
+fn trait_ref_may_hold<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                cause: &ObligationCause<'tcx>,
+                                trait_ref: ty::TraitRef<'tcx>)
+                                -> bool
+{
+    let obligation = predicate_for_trait_ref(cause.clone(), trait_ref, 0);
+    infcx.probe(|_| SelectionContext::new(infcx).evaluate_obligation(&obligation))
+}
+
 pub fn report_object_safety_error<'tcx>(tcx: &ty::ctxt<'tcx>,
                                         span: Span,
                                         trait_def_id: DefId,
                                         violations: Vec<ObjectSafetyViolation>,
                                         is_warning: bool)
 {
-    span_err_or_warn!(
-        is_warning, tcx.sess, span, E0038,
+    build_object_safety_error_report(tcx, span, trait_def_id, violations, is_warning)
+        .emit(tcx.sess);
+}
+
+/// Builds the report (E0038) for a trait that cannot be made into an
+/// object because of `violations`.
+fn build_object_safety_error_report<'tcx>(tcx: &ty::ctxt<'tcx>,
+                                          span: Span,
+                                          trait_def_id: DefId,
+                                          mut violations: Vec<ObjectSafetyViolation>,
+                                          is_warning: bool)
+                                          -> TraitErrorReport
+{
+    let mut report = trait_error_report!(
+        is_warning, span, E0038,
         "the trait `{}` cannot be made into an object",
         tcx.item_path_str(trait_def_id));
 
+    violations.sort_by(|a, b| object_safety_sort_key(a).cmp(&object_safety_sort_key(b)));
+
+    let only_generic_method = match (violations.len(), violations.first()) {
+        (1, Some(&ObjectSafetyViolation::Method(ref method, MethodViolationCode::Generic(_)))) => {
+            Some(method.name)
+        }
+        _ => None,
+    };
+
+    let fix_plan = object_safety_fix_plan(&violations);
+
     let mut reported_violations = FnvHashSet();
+    let mut pointed_at_methods = FnvHashSet();
     for violation in violations {
         if !reported_violations.insert(violation.clone()) {
             continue;
         }
+        let rule = object_safety_rule(&violation);
+        let method = match violation {
+            ObjectSafetyViolation::Method(ref method, _) => Some((method.def_id, method.name)),
+            _ => None,
+        };
         match violation {
             ObjectSafetyViolation::SizedSelf => {
-                tcx.sess.fileline_note(
+                report.note(
                     span,
                     "the trait cannot require that `Self : Sized`");
             }
 
             ObjectSafetyViolation::SupertraitSelf => {
-                tcx.sess.fileline_note(
+                report.note(
                     span,
                     "the trait cannot use `Self` as a type parameter \
                      in the supertrait listing");
@@ -357,7 +2982,7 @@ pub fn report_object_safety_error<'tcx>(tcx: &ty::ctxt<'tcx>,
 
             ObjectSafetyViolation::Method(method,
                                           MethodViolationCode::StaticMethod) => {
-                tcx.sess.fileline_note(
+                report.note(
                     span,
                     &format!("method `{}` has no receiver",
                              method.name));
@@ -365,7 +2990,7 @@ pub fn report_object_safety_error<'tcx>(tcx: &ty::ctxt<'tcx>,
 
             ObjectSafetyViolation::Method(method,
                                           MethodViolationCode::ReferencesSelf) => {
-                tcx.sess.fileline_note(
+                report.note(
                     span,
                     &format!("method `{}` references the `Self` type \
                               in its arguments or return type",
@@ -373,22 +2998,244 @@ pub fn report_object_safety_error<'tcx>(tcx: &ty::ctxt<'tcx>,
             }
 
             ObjectSafetyViolation::Method(method,
-                                          MethodViolationCode::Generic) => {
-                tcx.sess.fileline_note(
+                                          MethodViolationCode::Generic(params)) => {
+                report.note(
                     span,
                     &format!("method `{}` has generic type parameters",
                              method.name));
+                suggest_non_generic_method(tcx, span, &method, &params, &mut report);
+            }
+        }
+        if let Some((method_def_id, method_name)) = method {
+            // Methods from other crates have no span to point at.
+            let def_span = tcx.map.def_id_span(method_def_id, DUMMY_SP);
+            if def_span != DUMMY_SP && pointed_at_methods.insert(method_def_id) {
+                report.span_note(
+                    def_span,
+                    &format!("method `{}` declared here", method_name));
+            }
+        }
+        if tcx.sess.verbose() {
+            report.note(span, rule);
+        }
+    }
+
+    if fix_plan.len() == 1 {
+        report.help(span, &format!("to make the trait object-safe, {}", fix_plan[0]));
+    } else if !fix_plan.is_empty() {
+        let steps: Vec<String> = fix_plan.iter().enumerate()
+                                         .map(|(i, step)| format!("({}) {}", i + 1, step))
+                                         .collect();
+        report.help(span, &format!("to make the trait object-safe, {}",
+                                   steps.join("; ")));
+    }
+
+    if let Some(method_name) = only_generic_method {
+        suggest_object_alias_with_defaults(tcx, span, trait_def_id, method_name, &mut report);
+    }
+    report
+}
+
+/// When the object-safety error comes from a cast to a trait object,
+/// name each method that keeps the cast from being allowed. How to fix
+/// them is left to the fix plan that `build_object_safety_error_report`
+/// already gives.
+fn note_methods_blocking_object_cast<'tcx>(tcx: &ty::ctxt<'tcx>,
+                                           obligation: &PredicateObligation<'tcx>,
+                                           trait_def_id: DefId,
+                                           report: &mut TraitErrorReport)
+{
+    let code = unwrapped_cause_code(&obligation.cause.code);
+    match *code {
+        ObligationCauseCode::ObjectCastObligation(..) => {}
+        _ => return,
+    }
+
+    let trait_name = tcx.item_path_str(trait_def_id);
+    let mut reported = FnvHashSet();
+    for violation in &object_safety_violations(tcx, trait_def_id) {
+        match *violation {
+            ObjectSafetyViolation::Method(ref method, MethodViolationCode::StaticMethod) |
+            ObjectSafetyViolation::Method(ref method, MethodViolationCode::Generic(_)) => {
+                if !reported.insert(method.name) {
+                    continue;
+                }
+                report.note(
+                    obligation.cause.span,
+                    &format!("method `{}` could never be called through a `{}` object, \
+                              so this cast is not allowed",
+                             method.name,
+                             trait_name));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The canonical order of object-safety violations: trait-level ones
+/// first, then methods by name, and by kind of violation within a method.
+fn object_safety_sort_key(violation: &ObjectSafetyViolation) -> (usize, String, usize) {
+    match *violation {
+        ObjectSafetyViolation::SizedSelf => (0, String::new(), 0),
+        ObjectSafetyViolation::SupertraitSelf => (1, String::new(), 0),
+        ObjectSafetyViolation::Method(ref method, ref code) => {
+            let code_rank = match *code {
+                MethodViolationCode::StaticMethod => 0,
+                MethodViolationCode::ReferencesSelf => 1,
+                MethodViolationCode::Generic(_) => 2,
+            };
+            (2, method.name.to_string(), code_rank)
+        }
+    }
+}
+
+/// Condenses the violations into the fewest edits that would make the
+/// trait object-safe, trait-level edits first. Every offending method
+/// can be fixed the same way, by excluding it from trait objects with
+/// `where Self: Sized`, so they are grouped into a single edit.
+fn object_safety_fix_plan(violations: &[ObjectSafetyViolation]) -> Vec<String> {
+    let mut plan = vec![];
+    if violations.contains(&ObjectSafetyViolation::SizedSelf) {
+        plan.push("remove the `Self: Sized` requirement from the trait".to_string());
+    }
+    if violations.contains(&ObjectSafetyViolation::SupertraitSelf) {
+        plan.push("stop passing `Self` as a type parameter to its supertraits".to_string());
+    }
+
+    let mut method_names = vec![];
+    for violation in violations {
+        if let ObjectSafetyViolation::Method(ref method, _) = *violation {
+            let name = format!("`{}`", method.name);
+            if !method_names.contains(&name) {
+                method_names.push(name);
             }
         }
     }
+    match method_names.len() {
+        0 => {}
+        1 => plan.push(format!("add `where Self: Sized` to method {}", method_names[0])),
+        n => plan.push(format!("add `where Self: Sized` to the {} methods {}",
+                               n, method_names.join(", "))),
+    }
+    plan
+}
+
+/// For a generic method that breaks object safety, shows the two ways out:
+/// taking trait objects in place of its type parameters, or keeping it
+/// generic but off the trait object with `where Self: Sized`.
+fn suggest_non_generic_method<'tcx>(tcx: &ty::ctxt<'tcx>,
+                                    span: Span,
+                                    method: &ty::Method<'tcx>,
+                                    params: &[ast::Name],
+                                    report: &mut TraitErrorReport)
+{
+    let replacements = params.iter().map(|&param| {
+        // Use the parameter's first trait bound as the object type.
+        let bound = method.predicates.predicates.get_slice(subst::FnSpace).iter()
+            .filter_map(|predicate| match *predicate {
+                ty::Predicate::Trait(ref data) => match data.0.self_ty().sty {
+                    ty::TyParam(ref p) if p.name == param &&
+                                          tcx.lang_items.sized_trait() != Some(data.def_id()) => {
+                        Some(tcx.item_path_str(data.def_id()))
+                    }
+                    _ => None,
+                },
+                _ => None,
+            }).next();
+        match bound {
+            Some(bound) => format!("`&{}` for `{}`", bound, param),
+            None => format!("a trait object for `{}`", param),
+        }
+    }).collect::<Vec<_>>();
+    report.help(
+        span,
+        &format!("make `{}` non-generic by taking trait objects instead, eg {}",
+                 method.name,
+                 replacements.join(", ")));
+    report.help(
+        span,
+        &format!("or keep `{}` generic and add `where Self: Sized` to it, so that it \
+                  cannot be called on trait objects",
+                 method.name));
+}
+
+/// When a single generic method is all that keeps a trait with defaulted
+/// type parameters from being object-safe, show the alias that names a
+/// usable object type once that method is fixed.
+fn suggest_object_alias_with_defaults(tcx: &ty::ctxt,
+                                      span: Span,
+                                      trait_def_id: DefId,
+                                      method_name: ast::Name,
+                                      report: &mut TraitErrorReport)
+{
+    let trait_def = tcx.lookup_trait_def(trait_def_id);
+    let params = trait_def.generics.types.get_slice(subst::TypeSpace);
+    if params.is_empty() {
+        return;
+    }
+    // A default that mentions `Self` or another parameter cannot be
+    // written in a type alias.
+    let defaults: Option<Vec<String>> = params.iter().map(|param| {
+        param.default.and_then(|ty| {
+            if ty.has_param_types() || ty.has_self_ty() { None } else { Some(ty.to_string()) }
+        })
+    }).collect();
+    let defaults = match defaults {
+        Some(defaults) => defaults,
+        None => return,
+    };
+
+    let trait_name = tcx.item_name(trait_def_id);
+    report.note(
+        span,
+        &format!("once `{}` is excluded from trait objects with `where Self: Sized`, \
+                  the type parameter defaults give a usable object type: \
+                  `type {}Object = {}<{}>;`",
+                 method_name,
+                 trait_name,
+                 tcx.item_path_str(trait_def_id),
+                 defaults.join(", ")));
+}
+
+/// The object-safety rule (RFC 255) that each kind of violation breaks,
+/// explained under `-Z verbose` after the violation itself.
+fn object_safety_rule(violation: &ObjectSafetyViolation) -> &'static str {
+    match *violation {
+        ObjectSafetyViolation::SizedSelf =>
+            "object safety rule (RFC 255): trait objects are unsized, so a trait \
+             whose `Self` must be `Sized` can never be used as one",
+        ObjectSafetyViolation::SupertraitSelf =>
+            "object safety rule (RFC 255): the concrete `Self` type is erased in a \
+             trait object, so supertraits cannot be parameterized by it",
+        ObjectSafetyViolation::Method(_, MethodViolationCode::StaticMethod) =>
+            "object safety rule (RFC 255): methods are dispatched through the \
+             receiver's vtable, so every method needs a `self` receiver",
+        ObjectSafetyViolation::Method(_, MethodViolationCode::ReferencesSelf) =>
+            "object safety rule (RFC 255): the concrete `Self` type is erased in a \
+             trait object, so methods cannot take or return it except as the receiver",
+        ObjectSafetyViolation::Method(_, MethodViolationCode::Generic(_)) =>
+            "object safety rule (RFC 255): a vtable holds one entry per method, but \
+             generic methods need one instantiation per set of type arguments",
+    }
 }
 
 pub fn maybe_report_ambiguity<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
                                         obligation: &PredicateObligation<'tcx>) {
+    if let Some(report) = build_ambiguity_report(infcx, obligation) {
+        report.emit(infcx.tcx.sess);
+    }
+}
+
+/// Builds the report for an obligation that could not be resolved, or
+/// returns `None` if the ambiguity probably follows from an error already
+/// reported.
+fn build_ambiguity_report<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                    obligation: &PredicateObligation<'tcx>)
+                                    -> Option<TraitErrorReport> {
     // Unable to successfully determine, probably means
     // insufficient type information, but could mean
     // ambiguous impls. The latter *ought* to be a
-    // coherence violation, so we don't report it here.
+    // coherence violation, but we still report it here.
 
     let predicate = infcx.resolve_type_vars_if_possible(&obligation.predicate);
 
@@ -402,6 +3249,7 @@ pub fn maybe_report_ambiguity<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
             let self_ty = trait_ref.self_ty();
             let all_types = &trait_ref.substs().types;
             if all_types.references_error() {
+                None
             } else if all_types.needs_infer() {
                 // This is kind of a hack: it frequently happens that some earlier
                 // error prevents types from being fully inferred, and then we get
@@ -417,29 +3265,49 @@ pub fn maybe_report_ambiguity<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
                 // inhabited. But in any case I just threw in this check for
                 // has_errors() to be sure that compilation isn't happening
                 // anyway. In that case, why inundate the user.
-                if !infcx.tcx.sess.has_errors() {
-                    if
-                        infcx.tcx.lang_items.sized_trait()
-                        .map_or(false, |sized_id| sized_id == trait_ref.def_id())
-                    {
-                        need_type_info(infcx, obligation.cause.span, self_ty);
-                    } else {
-                        span_err!(infcx.tcx.sess, obligation.cause.span, E0283,
-                                "type annotations required: cannot resolve `{}`",
-                                predicate);
-                        note_obligation_cause(infcx, obligation);
-                    }
+                if infcx.tcx.sess.has_errors() {
+                    return None;
+                }
+                let literal = all_types.iter().filter_map(|&t| {
+                    literal_suffix_example(infcx, t)
+                }).next();
+                if let Some(example) = literal {
+                    let mut report = trait_error_report!(false, obligation.cause.span, E0283,
+                            "type annotations required: cannot infer the type of the \
+                             numeric literal needed to resolve `{}`",
+                            predicate);
+                    report.help(obligation.cause.span,
+                                &format!("consider giving the literal a suffix, e.g. `{}`",
+                                         example));
+                    note_obligation_cause(infcx, obligation, &mut report);
+                    Some(report)
+                } else if
+                    infcx.tcx.lang_items.sized_trait()
+                    .map_or(false, |sized_id| sized_id == trait_ref.def_id())
+                {
+                    Some(need_type_info(infcx, obligation, self_ty))
+                } else {
+                    let mut report = trait_error_report!(false, obligation.cause.span, E0283,
+                            "type annotations required: cannot resolve `{}`",
+                            predicate);
+                    help_ambiguity_candidates(infcx, obligation, data, &mut report);
+                    note_obligation_cause(infcx, obligation, &mut report);
+                    Some(report)
                 }
             } else if !infcx.tcx.sess.has_errors() {
-                // Ambiguity. Coherence should have reported an error.
-                infcx.tcx.sess.span_bug(
-                    obligation.cause.span,
-                    &format!(
-                        "coherence failed to report ambiguity: \
-                         cannot locate the impl of the trait `{}` for \
-                         the type `{}`",
-                        trait_ref,
-                        self_ty));
+                // Ambiguity between impls. Coherence ought to have reported
+                // the overlap, but an error is more useful than an ICE if
+                // it did not.
+                let mut report = trait_error_report!(false, obligation.cause.span, E0517,
+                        "multiple applicable implementations found for `{}`",
+                        predicate);
+                note_applicable_impls(infcx, obligation, data, &mut report);
+                report.help(obligation.cause.span,
+                            "annotate the types involved so that only one implementation applies");
+                note_obligation_cause(infcx, obligation, &mut report);
+                Some(report)
+            } else {
+                None
             }
         }
 
@@ -447,168 +3315,749 @@ pub fn maybe_report_ambiguity<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
             // Same hacky approach as above to avoid deluging user
             // with error messages.
             if !ty.references_error() && !infcx.tcx.sess.has_errors() {
-                need_type_info(infcx, obligation.cause.span, ty);
+                Some(need_type_info(infcx, obligation, ty))
+            } else {
+                None
+            }
+        }
+
+        ty::Predicate::Projection(ref data) => {
+            if infcx.tcx.sess.has_errors() {
+                return None;
             }
+            let mut report = trait_error_report!(false, obligation.cause.span, E0284,
+                    "type annotations required: cannot resolve `{}`",
+                    predicate);
+            note_unbound_associated_types(infcx, obligation.cause.span, data, &mut report);
+            note_obligation_cause(infcx, obligation, &mut report);
+            Some(report)
         }
 
         _ => {
-            if !infcx.tcx.sess.has_errors() {
-                span_err!(infcx.tcx.sess, obligation.cause.span, E0284,
-                        "type annotations required: cannot resolve `{}`",
-                        predicate);
-                note_obligation_cause(infcx, obligation);
+            if infcx.tcx.sess.has_errors() {
+                return None;
+            }
+            let mut report = trait_error_report!(false, obligation.cause.span, E0284,
+                    "type annotations required: cannot resolve `{}`",
+                    predicate);
+            note_obligation_cause(infcx, obligation, &mut report);
+            Some(report)
+        }
+    }
+}
+
+/// The most candidates that the help for an ambiguous trait obligation
+/// lists by name.
+const MAX_AMBIGUITY_CANDIDATES: usize = 4;
+
+/// Lists the impls and where-clauses that selection could not choose
+/// between for an ambiguous trait obligation.
+fn help_ambiguity_candidates<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                       obligation: &PredicateObligation<'tcx>,
+                                       data: &ty::PolyTraitPredicate<'tcx>,
+                                       report: &mut TraitErrorReport)
+{
+    let tcx = infcx.tcx;
+    let trait_obligation = obligation.with(data.clone());
+    let (impl_def_ids, where_clauses) =
+        SelectionContext::new(infcx).ambiguity_candidates(&trait_obligation);
+
+    let mut candidates: Vec<String> = impl_def_ids.into_iter().filter_map(|impl_def_id| {
+        tcx.impl_trait_ref(impl_def_id).map(|impl_trait_ref| {
+            format!("`impl {} for {}`", impl_trait_ref, impl_trait_ref.self_ty())
+        })
+    }).collect();
+    candidates.extend(where_clauses.into_iter().map(|where_clause| {
+        let where_clause = infcx.resolve_type_vars_if_possible(&where_clause);
+        format!("the where clause `{}: {}`", where_clause.self_ty(), where_clause)
+    }));
+    if candidates.len() < 2 {
+        return;
+    }
+
+    let others = candidates.len().saturating_sub(MAX_AMBIGUITY_CANDIDATES);
+    candidates.truncate(MAX_AMBIGUITY_CANDIDATES);
+    if others > 0 {
+        candidates.push(format!("{} other{}", others, if others == 1 { "" } else { "s" }));
+    }
+    report.help(obligation.cause.span,
+                &format!("annotate the types involved to select one of the candidates: {}",
+                         candidates.join(", ")));
+}
+
+/// Lists the impls that all apply to an ambiguous trait obligation.
+fn note_applicable_impls<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                   obligation: &PredicateObligation<'tcx>,
+                                   data: &ty::PolyTraitPredicate<'tcx>,
+                                   report: &mut TraitErrorReport)
+{
+    let tcx = infcx.tcx;
+    let trait_obligation = obligation.with(data.clone());
+    let mut impl_def_ids = vec![];
+    tcx.lookup_trait_def(data.def_id()).for_each_relevant_impl(
+        tcx,
+        data.0.trait_ref.self_ty(),
+        |impl_def_id| {
+            if SelectionContext::new(infcx).evaluate_impl(impl_def_id, &trait_obligation) {
+                impl_def_ids.push(impl_def_id);
+            }
+        });
+
+    for (i, impl_def_id) in impl_def_ids.into_iter().enumerate() {
+        let impl_trait_ref = match tcx.impl_trait_ref(impl_def_id) {
+            Some(impl_trait_ref) => impl_trait_ref,
+            None => continue,
+        };
+        let msg = format!("candidate #{}: `impl {} for {}`",
+                          i + 1, impl_trait_ref, impl_trait_ref.self_ty());
+        match tcx.map.as_local_node_id(impl_def_id) {
+            Some(node_id) => report.span_note(tcx.map.span(node_id), &msg),
+            None => report.note(obligation.cause.span, &msg),
+        }
+    }
+}
+
+/// For an ambiguous projection on a trait with several associated
+/// types, lists which of them are bound -- by the obligation itself or
+/// by a where-clause on the same trait reference -- and which are not.
+/// Partial bindings like `T: Graph<Node=u32>` are otherwise easy to miss.
+fn note_unbound_associated_types<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                           span: Span,
+                                           data: &ty::PolyProjectionPredicate<'tcx>,
+                                           report: &mut TraitErrorReport)
+{
+    let tcx = infcx.tcx;
+    let trait_ref = data.to_poly_trait_ref();
+    let def = tcx.lookup_trait_def(trait_ref.def_id());
+    if def.associated_type_names.len() < 2 {
+        return;
+    }
+
+    let mut bound_names = FnvHashSet();
+    bound_names.insert(data.item_name());
+    for caller_bound in &infcx.parameter_environment.caller_bounds {
+        if let ty::Predicate::Projection(ref bound_data) = *caller_bound {
+            let bound_trait_ref = bound_data.to_poly_trait_ref();
+            if bound_trait_ref.def_id() == trait_ref.def_id() &&
+               bound_trait_ref.self_ty() == trait_ref.self_ty() {
+                bound_names.insert(bound_data.item_name());
             }
         }
     }
+
+    if def.associated_type_names.iter().all(|name| bound_names.contains(name)) {
+        return;
+    }
+
+    let listing = def.associated_type_names.iter().map(|name| {
+        format!("`{}` ({})", name, if bound_names.contains(name) { "bound" } else { "unbound" })
+    }).collect::<Vec<_>>().join(", ");
+    report.note(
+        span,
+        &format!("the trait `{}` has associated types {}",
+                 tcx.item_path_str(trait_ref.def_id()),
+                 listing));
 }
 
 fn need_type_info<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
-                            span: Span,
+                            obligation: &PredicateObligation<'tcx>,
                             ty: Ty<'tcx>)
+                            -> TraitErrorReport
 {
-    span_err!(infcx.tcx.sess, span, E0282,
+    let span = obligation.cause.span;
+    if let Some(example) = literal_suffix_example(infcx, ty) {
+        let mut report = trait_error_report!(false, span, E0282,
+                  "unable to infer the type of a numeric literal");
+        report.help(span,
+                    &format!("consider giving the literal a suffix, e.g. `{}`", example));
+        return report;
+    }
+    let mut report = trait_error_report!(false, span, E0282,
               "unable to infer enough type information about `{}`; \
                type annotations or generic parameter binding required",
               ty);
+    if let Some(template) = turbofish_template(infcx, obligation) {
+        report.help(span,
+                    &format!("consider giving the type parameters explicitly, as in `{}`",
+                             template));
+    }
+    report
+}
+
+/// If `ty` is the type of a numeric literal that inference left
+/// unconstrained, an example of such a literal with a suffix.
+fn literal_suffix_example<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                    ty: Ty<'tcx>)
+                                    -> Option<&'static str>
+{
+    match infcx.type_is_unconstrained_numeric(ty) {
+        UnconstrainedNumeric::UnconstrainedInt => Some("0i32"),
+        UnconstrainedNumeric::UnconstrainedFloat => Some("0.0f64"),
+        UnconstrainedNumeric::Neither => None,
+    }
+}
+
+/// If the obligation was incurred by a call whose type parameters are
+/// still unresolved, renders the call with `_` written in for each of
+/// them, e.g. `Vec::<_>::new()` or `.collect::<_>()`.
+fn turbofish_template<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                obligation: &PredicateObligation<'tcx>)
+                                -> Option<String>
+{
+    let tcx = infcx.tcx;
+    let mut finder = CallFinder { span: obligation.cause.span, call: None };
+    match tcx.map.find(enclosing_item_id(tcx, obligation.cause.body_id)) {
+        Some(ast_map::NodeItem(item)) => finder.visit_item(item),
+        Some(ast_map::NodeTraitItem(item)) => finder.visit_trait_item(item),
+        Some(ast_map::NodeImplItem(item)) => finder.visit_impl_item(item),
+        _ => return None,
+    }
+    let expr = match finder.call {
+        Some(expr) => expr,
+        None => return None,
+    };
+
+    // Only the parameter spaces that inference failed to fill in get
+    // placeholders; the rest are left for the user to see as written.
+    let unresolved = |substs: &subst::Substs<'tcx>, space: subst::ParamSpace| {
+        let types = substs.types.get_slice(space);
+        if types.iter().any(|t| infcx.resolve_type_vars_if_possible(t).needs_infer()) {
+            types.len()
+        } else {
+            0
+        }
+    };
+    let placeholders = |count: usize| {
+        if count == 0 {
+            String::new()
+        } else {
+            format!("::<{}>", vec!["_"; count].join(", "))
+        }
+    };
+
+    let tables = infcx.tables.borrow();
+    match expr.node {
+        hir::ExprCall(ref callee, _) => {
+            let path = match callee.node {
+                hir::ExprPath(None, ref path) => path,
+                _ => return None,
+            };
+            if path.segments.iter().any(|segment| !segment.parameters.is_empty()) {
+                return None;
+            }
+            let substs = match tables.item_substs.get(&callee.id) {
+                Some(item_substs) => &item_substs.substs,
+                None => return None,
+            };
+            let fn_params = unresolved(substs, subst::FnSpace);
+            let type_params = unresolved(substs, subst::TypeSpace);
+            if fn_params + type_params == 0 {
+                return None;
+            }
+            let last = path.segments.len() - 1;
+            let segments = path.segments.iter().enumerate().map(|(i, segment)| {
+                let count = if i == last {
+                    fn_params
+                } else if i + 1 == last {
+                    type_params
+                } else {
+                    0
+                };
+                format!("{}{}", segment.identifier.name, placeholders(count))
+            }).collect::<Vec<_>>();
+            Some(format!("{}()", segments.join("::")))
+        }
+        hir::ExprMethodCall(name, ref tys, _) if tys.is_empty() => {
+            let callee = match tables.method_map.get(&ty::MethodCall::expr(expr.id)) {
+                Some(callee) => callee,
+                None => return None,
+            };
+            match unresolved(callee.substs, subst::FnSpace) {
+                0 => None,
+                count => Some(format!(".{}{}()", name.node, placeholders(count))),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Finds the call expression, if any, whose span or callee span is the
+/// given span.
+struct CallFinder<'v> {
+    span: Span,
+    call: Option<&'v hir::Expr>,
+}
+
+impl<'v> Visitor<'v> for CallFinder<'v> {
+    fn visit_expr(&mut self, e: &'v hir::Expr) {
+        if self.call.is_some() {
+            return;
+        }
+        match e.node {
+            hir::ExprCall(ref callee, _) if callee.span == self.span || e.span == self.span => {
+                self.call = Some(e);
+                return;
+            }
+            hir::ExprMethodCall(..) if e.span == self.span => {
+                self.call = Some(e);
+                return;
+            }
+            _ => {}
+        }
+        visit::walk_expr(self, e);
+    }
+}
+
+/// Finds the operator expression, if any, whose span is the given span.
+struct OperatorFinder<'v> {
+    span: Span,
+    expr: Option<&'v hir::Expr>,
+}
+
+impl<'v> Visitor<'v> for OperatorFinder<'v> {
+    fn visit_expr(&mut self, e: &'v hir::Expr) {
+        if self.expr.is_some() {
+            return;
+        }
+        match e.node {
+            hir::ExprBinary(..) |
+            hir::ExprAssignOp(..) |
+            hir::ExprUnary(..) if e.span == self.span => {
+                self.expr = Some(e);
+                return;
+            }
+            _ => {}
+        }
+        visit::walk_expr(self, e);
+    }
 }
 
 fn note_obligation_cause<'a, 'tcx, T>(infcx: &InferCtxt<'a, 'tcx>,
-                                      obligation: &Obligation<'tcx, T>)
+                                      obligation: &Obligation<'tcx, T>,
+                                      report: &mut TraitErrorReport)
     where T: fmt::Display
 {
     note_obligation_cause_code(infcx,
                                &obligation.predicate,
                                obligation.cause.span,
-                               &obligation.cause.code);
+                               &obligation.cause.code,
+                               report);
 }
 
+/// The most "required because ..." notes shown for the chain of derived
+/// obligations behind an error; the rest, save the outermost, are elided
+/// unless `-Z verbose` is given.
+const MAX_CAUSE_CHAIN_NOTES: usize = 10;
+
 fn note_obligation_cause_code<'a, 'tcx, T>(infcx: &InferCtxt<'a, 'tcx>,
                                            predicate: &T,
                                            cause_span: Span,
-                                           cause_code: &ObligationCauseCode<'tcx>)
+                                           cause_code: &ObligationCauseCode<'tcx>,
+                                           report: &mut TraitErrorReport)
+    where T: fmt::Display
+{
+    let mut chain = CauseChain {
+        max_notes: if infcx.tcx.sess.verbose() { usize::MAX } else { MAX_CAUSE_CHAIN_NOTES },
+        len: derived_trait_refs(cause_code).len(),
+        frame: 0,
+        elided: 0,
+        visited: FnvHashSet(),
+        notes: FnvHashSet(),
+    };
+    note_obligation_cause_chain(infcx, predicate, cause_span, cause_code, &mut chain, report);
+}
+
+/// Tracks the derived obligations noted so far for one error.
+struct CauseChain<'tcx> {
+    /// The most derived obligations to note before eliding.
+    max_notes: usize,
+    /// The number of derived obligations in the chain.
+    len: usize,
+    /// The index of the next derived obligation.
+    frame: usize,
+    /// The number of derived obligations elided so far.
+    elided: usize,
+    /// The parent trait references already noted.
+    visited: FnvHashSet<ty::PolyTraitRef<'tcx>>,
+    /// The notes already emitted. Different parents can still render
+    /// the same text, e.g. the builtin-derived notes, which only name
+    /// the self type.
+    notes: FnvHashSet<String>,
+}
+
+impl<'tcx> CauseChain<'tcx> {
+    /// Decides whether to note the next derived obligation, whose parent
+    /// is `parent`. The innermost `max_notes - 1` and the outermost, which
+    /// leads to the "required by" note, are always shown, the latter
+    /// preceded by a count of the ones in between that were not; repeated
+    /// parents are skipped.
+    fn show_frame(&mut self,
+                  span: Span,
+                  parent: ty::PolyTraitRef<'tcx>,
+                  report: &mut TraitErrorReport)
+                  -> bool {
+        let index = self.frame;
+        self.frame += 1;
+        let is_new = self.visited.insert(parent);
+        if self.len <= self.max_notes || index + 1 < self.max_notes {
+            return is_new;
+        }
+        if index + 1 < self.len {
+            if is_new {
+                self.elided += 1;
+            }
+            return false;
+        }
+        if self.elided > 0 {
+            report.note(span, &format!("...and {} more", self.elided));
+        }
+        is_new
+    }
+}
+
+fn note_obligation_cause_chain<'a, 'tcx, T>(infcx: &InferCtxt<'a, 'tcx>,
+                                            predicate: &T,
+                                            cause_span: Span,
+                                            cause_code: &ObligationCauseCode<'tcx>,
+                                            chain: &mut CauseChain<'tcx>,
+                                            report: &mut TraitErrorReport)
     where T: fmt::Display
 {
     let tcx = infcx.tcx;
+    let show = match *cause_code {
+        ObligationCauseCode::BuiltinDerivedObligation(ref data) |
+        ObligationCauseCode::ImplDerivedObligation(ref data) => {
+            let parent_trait_ref = infcx.resolve_type_vars_if_possible(&data.parent_trait_ref);
+            chain.show_frame(cause_span, tcx.erase_regions(&parent_trait_ref), report)
+        }
+        _ => true,
+    };
+    if show {
+        if let Some(msg) = cause_code_message(infcx, predicate, cause_code) {
+            if !chain.notes.contains(&msg) {
+                report.note(cause_span, &msg);
+                chain.notes.insert(msg);
+            }
+        }
+    }
     match *cause_code {
-        ObligationCauseCode::MiscObligation => { }
         ObligationCauseCode::RFC1214(ref subcode) => {
-            tcx.sess.note_rfc_1214(cause_span);
-            note_obligation_cause_code(infcx, predicate, cause_span, subcode);
+            if !infcx.suppress_rfc1214_note.get() {
+                report.rfc_1214(cause_span);
+            }
+            note_obligation_cause_chain(infcx, predicate, cause_span, subcode, chain, report);
+        }
+        ObligationCauseCode::FeatureMigration(feature, ref subcode) => {
+            if is_feature_warning(tcx.sess, cause_code) {
+                report.note(cause_span,
+                            &format!("this is a warning only because `-Z feature-trait-warnings` \
+                                      lists the feature `{}`, which introduced the requirement",
+                                     feature));
+            }
+            note_obligation_cause_chain(infcx, predicate, cause_span, subcode, chain, report);
+        }
+        ObligationCauseCode::ClosureCapture(var_id, closure_span, builtin_bound, capture) => {
+            note_closure_capture_mode(infcx, var_id, closure_span, builtin_bound, capture,
+                                      report);
+        }
+        ObligationCauseCode::CompareImplMethodObligation(trait_method_def_id, _) => {
+            note_trait_method_for_impl_bound(tcx, cause_span, trait_method_def_id, predicate,
+                                             report);
+        }
+        ObligationCauseCode::BuiltinDerivedObligation(ref data) |
+        ObligationCauseCode::ImplDerivedObligation(ref data) => {
+            let parent_trait_ref = infcx.resolve_type_vars_if_possible(&data.parent_trait_ref);
+            let parent_predicate = parent_trait_ref.to_predicate();
+            note_obligation_cause_chain(infcx, &parent_predicate, cause_span,
+                                        &*data.parent_code, chain, report);
         }
+        _ => { }
+    }
+}
+
+/// The text of the note that `note_obligation_cause_code` emits for
+/// `cause_code` itself, leaving out the notes for any parent causes.
+/// Returns `None` for causes that get no note of their own.
+pub fn cause_code_message<'a, 'tcx, T>(infcx: &InferCtxt<'a, 'tcx>,
+                                       predicate: &T,
+                                       cause_code: &ObligationCauseCode<'tcx>)
+                                       -> Option<String>
+    where T: fmt::Display
+{
+    let tcx = infcx.tcx;
+    let msg = match *cause_code {
+        ObligationCauseCode::MiscObligation |
+        ObligationCauseCode::RFC1214(..) |
+        ObligationCauseCode::FeatureMigration(..) => return None,
         ObligationCauseCode::SliceOrArrayElem => {
-            tcx.sess.fileline_note(
-                cause_span,
-                &format!("slice and array elements must have `Sized` type"));
+            "slice and array elements must have `Sized` type".to_string()
         }
         ObligationCauseCode::ProjectionWf(data) => {
-            tcx.sess.fileline_note(
-                cause_span,
-                &format!("required so that the projection `{}` is well-formed",
-                         data));
+            format!("required so that the projection `{}` is well-formed",
+                    data)
         }
         ObligationCauseCode::ReferenceOutlivesReferent(ref_ty) => {
-            tcx.sess.fileline_note(
-                cause_span,
-                &format!("required so that reference `{}` does not outlive its referent",
-                         ref_ty));
+            format!("required so that reference `{}` does not outlive its referent",
+                    ref_ty)
         }
         ObligationCauseCode::ItemObligation(item_def_id) => {
-            let item_name = tcx.item_path_str(item_def_id);
-            tcx.sess.fileline_note(
-                cause_span,
-                &format!("required by `{}`", item_name));
+            format!("required by `{}`", tcx.item_path_str(item_def_id))
+        }
+        ObligationCauseCode::SupertraitObligation(trait_def_id, supertrait_def_id) => {
+            format!("required because `{}` has supertrait `{}`",
+                    tcx.item_path_str(trait_def_id),
+                    tcx.item_path_str(supertrait_def_id))
         }
         ObligationCauseCode::ObjectCastObligation(object_ty) => {
-            tcx.sess.fileline_note(
-                cause_span,
-                &format!(
-                    "required for the cast to the object type `{}`",
-                    infcx.ty_to_string(object_ty)));
+            format!("required for the cast to the object type `{}`",
+                    infcx.ty_to_string(object_ty))
         }
         ObligationCauseCode::RepeatVec => {
-            tcx.sess.fileline_note(
-                cause_span,
-                "the `Copy` trait is required because the \
-                 repeated element will be copied");
+            "the `Copy` trait is required because the \
+             repeated element will be copied".to_string()
         }
         ObligationCauseCode::VariableType(_) => {
-            tcx.sess.fileline_note(
-                cause_span,
-                "all local variables must have a statically known size");
+            "all local variables must have a statically known size".to_string()
         }
         ObligationCauseCode::ReturnType => {
-            tcx.sess.fileline_note(
-                cause_span,
-                "the return type of a function must have a \
-                 statically known size");
+            "the return type of a function must have a \
+             statically known size".to_string()
         }
         ObligationCauseCode::AssignmentLhsSized => {
-            tcx.sess.fileline_note(
-                cause_span,
-                "the left-hand-side of an assignment must have a statically known size");
+            "the left-hand-side of an assignment must have a statically known size".to_string()
         }
         ObligationCauseCode::StructInitializerSized => {
-            tcx.sess.fileline_note(
-                cause_span,
-                "structs must have a statically known size to be initialized");
+            "structs must have a statically known size to be initialized".to_string()
         }
-        ObligationCauseCode::ClosureCapture(var_id, _, builtin_bound) => {
+        ObligationCauseCode::ClosureCapture(var_id, _, builtin_bound, _) => {
             let def_id = tcx.lang_items.from_builtin_kind(builtin_bound).unwrap();
-            let trait_name = tcx.item_path_str(def_id);
-            let name = tcx.local_var_name_str(var_id);
-            tcx.sess.fileline_note(
-                cause_span,
-                &format!("the closure that captures `{}` requires that all captured variables \
-                          implement the trait `{}`",
-                         name,
-                         trait_name));
+            format!("the closure that captures `{}` requires that all captured variables \
+                     implement the trait `{}`",
+                    tcx.local_var_name_str(var_id),
+                    tcx.item_path_str(def_id))
         }
         ObligationCauseCode::FieldSized => {
-            tcx.sess.fileline_note(
-                cause_span,
-                "only the last field of a struct or enum variant \
-                 may have a dynamically sized type");
+            "only the last field of a struct or enum variant \
+             may have a dynamically sized type".to_string()
         }
         ObligationCauseCode::SharedStatic => {
-            tcx.sess.fileline_note(
-                cause_span,
-                "shared static variables must have a type that implements `Sync`");
+            "shared static variables must have a type that implements `Sync`".to_string()
         }
         ObligationCauseCode::BuiltinDerivedObligation(ref data) => {
             let parent_trait_ref = infcx.resolve_type_vars_if_possible(&data.parent_trait_ref);
-            tcx.sess.fileline_note(
-                cause_span,
-                &format!("required because it appears within the type `{}`",
-                         parent_trait_ref.0.self_ty()));
-            let parent_predicate = parent_trait_ref.to_predicate();
-            note_obligation_cause_code(infcx, &parent_predicate, cause_span, &*data.parent_code);
+            match data.parent_variant {
+                Some(variant) => {
+                    format!("required because it appears within the variant `{}` \
+                             of the type `{}`",
+                            variant,
+                            parent_trait_ref.0.self_ty())
+                }
+                None => {
+                    format!("required because it appears within the type `{}`",
+                            parent_trait_ref.0.self_ty())
+                }
+            }
         }
         ObligationCauseCode::ImplDerivedObligation(ref data) => {
             let parent_trait_ref = infcx.resolve_type_vars_if_possible(&data.parent_trait_ref);
-            tcx.sess.fileline_note(
-                cause_span,
-                &format!("required because of the requirements on the impl of `{}` for `{}`",
-                         parent_trait_ref,
-                         parent_trait_ref.0.self_ty()));
-            let parent_predicate = parent_trait_ref.to_predicate();
-            note_obligation_cause_code(infcx, &parent_predicate, cause_span, &*data.parent_code);
+            format!("required because of the requirements on the impl of `{}` for `{}`",
+                    parent_trait_ref,
+                    parent_trait_ref.0.self_ty())
         }
-        ObligationCauseCode::CompareImplMethodObligation => {
-            tcx.sess.fileline_note(
-                cause_span,
-                &format!("the requirement `{}` appears on the impl method \
-                          but not on the corresponding trait method",
-                         predicate));
+        ObligationCauseCode::CompareImplMethodObligation(..) => {
+            format!("the requirement `{}` appears on the impl method \
+                     but not on the corresponding trait method",
+                    predicate)
         }
+    };
+    Some(msg)
+}
+
+/// Points at the first use of `var_id` inside the closure whose capture
+/// of it gave rise to a `ClosureCapture` obligation, and at the closure,
+/// saying how the variable is captured.
+fn note_closure_capture_mode<'a, 'tcx>(infcx: &InferCtxt<'a, 'tcx>,
+                                       var_id: ast::NodeId,
+                                       closure_span: Span,
+                                       builtin_bound: ty::BuiltinBound,
+                                       capture: ty::UpvarCapture,
+                                       report: &mut TraitErrorReport)
+{
+    let tcx = infcx.tcx;
+    let capture_span = tcx.freevars.borrow().values()
+        .flat_map(|freevars| freevars.iter())
+        .filter(|freevar| {
+            freevar.def.var_id() == var_id &&
+                closure_span.lo <= freevar.span.lo && freevar.span.hi <= closure_span.hi
+        })
+        .map(|freevar| freevar.span)
+        .min_by(|span| span.lo);
+    if let Some(capture_span) = capture_span {
+        report.span_note(capture_span,
+                         &format!("`{}` captured here", tcx.local_var_name_str(var_id)));
     }
+
+    let var_ty = match infcx.tables.borrow().node_types.get(&var_id) {
+        Some(&var_ty) => infcx.resolve_type_vars_if_possible(&var_ty),
+        None => return,
+    };
+    let def_id = tcx.lang_items.from_builtin_kind(builtin_bound).unwrap();
+    let (mode, captured_ty) = match capture {
+        ty::UpvarCapture::ByValue =>
+            ("by value", format!("{}", var_ty)),
+        ty::UpvarCapture::ByRef(ty::UpvarBorrow { kind: ty::MutBorrow, .. }) =>
+            ("by mutable reference", format!("&mut {}", var_ty)),
+        ty::UpvarCapture::ByRef(..) =>
+            ("by reference", format!("&{}", var_ty)),
+    };
+    report.span_note(
+        closure_span,
+        &format!("`{}` is captured {} here, which requires `{}: {}`",
+                 tcx.local_var_name_str(var_id),
+                 mode,
+                 captured_ty,
+                 tcx.item_path_str(def_id)));
 }
 
-fn suggest_new_overflow_limit(tcx: &ty::ctxt, span: Span) {
-    let current_limit = tcx.sess.recursion_limit.get();
-    let suggested_limit = current_limit * 2;
-    tcx.sess.fileline_note(
+/// The `#![recursion_limit]` value that the E0275 note suggests. Exposed
+/// so that tools can offer to update the attribute themselves.
+pub fn suggested_recursion_limit(tcx: &ty::ctxt) -> usize {
+    tcx.sess.recursion_limit.get() * 2
+}
+
+fn suggest_new_overflow_limit(tcx: &ty::ctxt, span: Span, report: &mut TraitErrorReport) {
+    report.note(
         span,
         &format!(
             "consider adding a `#![recursion_limit=\"{}\"]` attribute to your crate",
-            suggested_limit));
+            suggested_recursion_limit(tcx)));
+}
+
+/// Under `-Z stable-trait-error-names`, renames the inference variables
+/// (`_#12t`, `_#3i`, `_#0f`) and region variables (`'_#4r`) that verbose
+/// output shows to sequential names (`_0`, `'0`), numbered afresh for
+/// each error and shared with its notes, so that test expectations do
+/// not depend on how many variables were created before the error.
+struct StableNames {
+    enabled: bool,
+    /// The `(original, stable)` variable names handed out so far.
+    names: Vec<(String, String)>,
+}
+
+impl StableNames {
+    fn new(sess: &Session) -> StableNames {
+        StableNames {
+            enabled: sess.opts.debugging_opts.stable_trait_error_names,
+            names: vec![],
+        }
+    }
+
+    fn apply<'a>(&mut self, msg: &'a str) -> Cow<'a, str> {
+        if !self.enabled || !msg.contains("_#") {
+            return Cow::Borrowed(msg);
+        }
+        let names = &mut self.names;
+        let mut out = String::with_capacity(msg.len());
+        let mut rest = msg;
+        while let Some(start) = rest.find("_#") {
+            let digits = rest[start + 2..].chars().take_while(|c| c.is_digit(10)).count();
+            let kind = rest[start + 2 + digits..].chars().next().unwrap_or(' ');
+            if digits == 0 || !"tifr".contains(kind) {
+                out.push_str(&rest[..start + 2]);
+                rest = &rest[start + 2..];
+                continue;
+            }
+            let end = start + 2 + digits + 1;
+            let original = &rest[start..end];
+            let is_region = kind == 'r';
+            let known = names.iter().find(|&&(ref o, _)| o == original).map(|&(_, ref s)| {
+                s.clone()
+            });
+            let stable = known.unwrap_or_else(|| {
+                let n = names.iter().filter(|&&(ref o, _)| o.ends_with('r') == is_region).count();
+                // The `'` of a region is kept from the original text.
+                let stable = if is_region { n.to_string() } else { format!("_{}", n) };
+                names.push((original.to_string(), stable.clone()));
+                stable
+            });
+            out.push_str(&rest[..start]);
+            out.push_str(&stable);
+            rest = &rest[end..];
+        }
+        out.push_str(rest);
+        Cow::Owned(out)
+    }
+}
+
+/// Emits a trait error (or, for RFC1214 obligations, a warning). In the
+/// default mode this is just `span_err_with_code`/`span_warn_with_code`;
+/// under `-Z gcc-style-trait-errors` it is rendered as a single
+/// `file:line:col: error: msg [CODE]` line instead.
+fn emit_err_or_warn(sess: &Session, is_warning: bool, span: Span, code: &str, msg: &str) {
+    if !sess.opts.debugging_opts.gcc_style_trait_errors {
+        if is_warning {
+            sess.span_warn_with_code(span, msg, code);
+        } else {
+            sess.span_err_with_code(span, msg, code);
+        }
+    } else if is_warning {
+        if sess.can_print_warnings {
+            emit_gcc_style_line(sess, span, Warning, Some(code), msg);
+        }
+    } else {
+        if sess.opts.treat_err_as_bug {
+            sess.span_bug(span, msg);
+        }
+        emit_gcc_style_line(sess, span, Error, Some(code), msg);
+    }
+}
+
+/// Emits a note attached to the preceding error, rendered like the trait
+/// errors themselves under `-Z gcc-style-trait-errors`.
+pub fn emit_note(sess: &Session, span: Span, msg: &str) {
+    if sess.opts.debugging_opts.gcc_style_trait_errors {
+        emit_gcc_style_line(sess, span, Note, None, msg);
+    } else {
+        sess.fileline_note(span, msg);
+    }
+}
+
+fn emit_help(sess: &Session, span: Span, msg: &str) {
+    if sess.opts.debugging_opts.gcc_style_trait_errors {
+        emit_gcc_style_line(sess, span, Help, None, msg);
+    } else {
+        sess.fileline_help(span, msg);
+    }
+}
+
+fn emit_suggestion(sess: &Session, span: Span, msg: &str, suggestion: String) {
+    if sess.opts.debugging_opts.gcc_style_trait_errors {
+        emit_gcc_style_line(sess, span, Help, None,
+                            &format!("{}: `{}`", msg, suggestion));
+    } else {
+        sess.span_suggestion(span, msg, suggestion);
+    }
+}
+
+/// Emits a note that, unlike `emit_note`, also shows the source at `span`.
+fn emit_span_note(sess: &Session, span: Span, msg: &str) {
+    if sess.opts.debugging_opts.gcc_style_trait_errors {
+        emit_gcc_style_line(sess, span, Note, None, msg);
+    } else {
+        sess.span_note(span, msg);
+    }
+}
+
+fn note_rfc_1214(sess: &Session, span: Span) {
+    if sess.opts.debugging_opts.gcc_style_trait_errors {
+        emit_gcc_style_line(sess, span, Note, None,
+                            "this warning will become a HARD ERROR in the next release \
+                             (see RFC 1214)");
+    } else {
+        sess.note_rfc_1214(span);
+    }
+}
+
+fn emit_gcc_style_line(sess: &Session, span: Span, level: Level, code: Option<&str>, msg: &str) {
+    sess.diagnostic().gcc_style(span, msg, code, level);
 }
@@ -93,6 +93,12 @@ pub struct FulfillmentContext<'tcx> {
     region_obligations: NodeMap<Vec<RegionObligation<'tcx>>>,
 
     pub errors_will_be_reported: bool,
+
+    // If true, an obligation that hits the recursion limit is reported
+    // (as E0275) and fails like any other selection error, instead of
+    // aborting compilation, so that independent overflows are all
+    // reported. Only set this if every error will be reported.
+    pub recover_from_overflow: bool,
 }
 
 #[derive(Clone)]
@@ -127,6 +133,7 @@ impl<'tcx> FulfillmentContext<'tcx> {
             attempted_mark: 0,
             region_obligations: NodeMap(),
             errors_will_be_reported: errors_will_be_reported,
+            recover_from_overflow: false,
         }
     }
 
@@ -243,7 +250,7 @@ impl<'tcx> FulfillmentContext<'tcx> {
                                       infcx: &InferCtxt<'a,'tcx>)
                                       -> Result<(),Vec<FulfillmentError<'tcx>>>
     {
-        let mut selcx = SelectionContext::new(infcx);
+        let mut selcx = self.selection_context(infcx);
         self.select(&mut selcx, true)
     }
 
@@ -251,10 +258,19 @@ impl<'tcx> FulfillmentContext<'tcx> {
                                      infcx: &InferCtxt<'a,'tcx>)
                                      -> Result<(),Vec<FulfillmentError<'tcx>>>
     {
-        let mut selcx = SelectionContext::new(infcx);
+        let mut selcx = self.selection_context(infcx);
         self.select(&mut selcx, false)
     }
 
+    fn selection_context<'a>(&self, infcx: &'a InferCtxt<'a,'tcx>)
+                             -> SelectionContext<'a,'tcx> {
+        if self.recover_from_overflow {
+            SelectionContext::recovering_from_overflow(infcx)
+        } else {
+            SelectionContext::new(infcx)
+        }
+    }
+
     pub fn pending_obligations(&self) -> &[PredicateObligation<'tcx>] {
         &self.predicates
     }
@@ -27,10 +27,19 @@ use syntax::ast;
 use syntax::codemap::{Span, DUMMY_SP};
 
 pub use self::error_reporting::TraitErrorKey;
+pub use self::error_reporting::{TraitErrorChild, TraitErrorReport};
+pub use self::error_reporting::cause_code_message;
+pub use self::error_reporting::emit_note;
+pub use self::error_reporting::in_source_order;
+pub use self::error_reporting::positional_argument_span;
 pub use self::error_reporting::report_fulfillment_errors;
 pub use self::error_reporting::report_overflow_error;
+pub use self::error_reporting::report_overflow_error_recoverable;
 pub use self::error_reporting::report_selection_error;
+pub use self::error_reporting::build_selection_error_report;
 pub use self::error_reporting::report_object_safety_error;
+pub use self::error_reporting::report_unimplemented_trait_summary;
+pub use self::error_reporting::suggested_recursion_limit;
 pub use self::coherence::orphan_check;
 pub use self::coherence::overlapping_impls;
 pub use self::coherence::OrphanCheckErr;
@@ -108,6 +117,11 @@ pub enum ObligationCauseCode<'tcx> {
     /// Obligation that triggers warning until RFC 1214 is fully in place.
     RFC1214(Rc<ObligationCauseCode<'tcx>>),
 
+    /// Obligation introduced by the named experimental feature. Its
+    /// failures are warnings if the feature is listed in
+    /// `-Z feature-trait-warnings`, and errors otherwise.
+    FeatureMigration(ast::Name, Rc<ObligationCauseCode<'tcx>>),
+
     /// This is the trait reference from the given projection
     SliceOrArrayElem,
 
@@ -118,6 +132,10 @@ pub enum ObligationCauseCode<'tcx> {
     /// also implement all supertraits of X.
     ItemObligation(DefId),
 
+    /// The trait (first) requires the supertrait (second) of whatever
+    /// implements it; e.g. an impl of the trait needs its supertraits.
+    SupertraitObligation(DefId, DefId),
+
     /// A type like `&'a T` is WF only if `T: 'a`.
     ReferenceOutlivesReferent(Ty<'tcx>),
 
@@ -132,8 +150,8 @@ pub enum ObligationCauseCode<'tcx> {
     RepeatVec,                 // [T,..n] --> T must be Copy
 
     // Captures of variable the given id by a closure (span is the
-    // span of the closure)
-    ClosureCapture(ast::NodeId, Span, ty::BuiltinBound),
+    // span of the closure), and how the closure captures it
+    ClosureCapture(ast::NodeId, Span, ty::BuiltinBound, ty::UpvarCapture),
 
     // Types of fields (other than the last) in a struct must be sized.
     FieldSized,
@@ -145,7 +163,10 @@ pub enum ObligationCauseCode<'tcx> {
 
     ImplDerivedObligation(DerivedObligationCause<'tcx>),
 
-    CompareImplMethodObligation,
+    // Bounds that the impl method declares but the trait method does
+    // not (the set difference of their where-clauses); the `DefId` is
+    // that of the trait method.
+    CompareImplMethodObligation(DefId, Rc<Vec<ty::Predicate<'tcx>>>),
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -156,6 +177,10 @@ pub struct DerivedObligationCause<'tcx> {
     /// directly.
     parent_trait_ref: ty::PolyTraitRef<'tcx>,
 
+    /// If the parent's self type is an enum and the current obligation
+    /// is for the payload of one of its variants, that variant's name.
+    parent_variant: Option<ast::Name>,
+
     /// The parent trait had this cause
     parent_code: Rc<ObligationCauseCode<'tcx>>
 }
@@ -173,8 +198,12 @@ pub enum SelectionError<'tcx> {
                                 ty::PolyTraitRef<'tcx>,
                                 ty::error::TypeError<'tcx>),
     TraitNotObjectSafe(DefId),
+    /// The recursion limit was hit; the overflow has already been
+    /// reported (as E0275) where it happened.
+    Overflow,
 }
 
+#[derive(Clone)]
 pub struct FulfillmentError<'tcx> {
     pub obligation: PredicateObligation<'tcx>,
     pub code: FulfillmentErrorCode<'tcx>
@@ -548,6 +577,20 @@ impl<'tcx> ObligationCauseCode<'tcx> {
             _ => false,
         }
     }
+
+    /// The feature that introduced this obligation, or the obligation
+    /// it was derived from, if any.
+    pub fn migrating_feature(&self) -> Option<ast::Name> {
+        match *self {
+            ObligationCauseCode::FeatureMigration(feature, _) => Some(feature),
+            ObligationCauseCode::RFC1214(ref code) => code.migrating_feature(),
+            ObligationCauseCode::BuiltinDerivedObligation(ref data) |
+            ObligationCauseCode::ImplDerivedObligation(ref data) => {
+                data.parent_code.migrating_feature()
+            }
+            _ => None,
+        }
+    }
 }
 
 impl<'tcx, N> Vtable<'tcx, N> {
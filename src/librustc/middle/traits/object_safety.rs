@@ -41,7 +41,7 @@ pub enum ObjectSafetyViolation<'tcx> {
 }
 
 /// Reasons a method might not be object-safe.
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum MethodViolationCode {
     /// e.g., `fn foo()`
     StaticMethod,
@@ -49,8 +49,8 @@ pub enum MethodViolationCode {
     /// e.g., `fn foo(&self, x: Self)` or `fn foo(&self) -> Self`
     ReferencesSelf,
 
-    /// e.g., `fn foo<A>()`
-    Generic,
+    /// e.g., `fn foo<A>()`; carries the names of the type parameters
+    Generic(Vec<ast::Name>),
 }
 
 pub fn is_object_safe<'tcx>(tcx: &ty::ctxt<'tcx>,
@@ -279,7 +279,10 @@ fn virtual_call_violation_for_method<'tcx>(tcx: &ty::ctxt<'tcx>,
 
     // We can't monomorphize things like `fn foo<A>(...)`.
     if !method.generics.types.is_empty_in(subst::FnSpace) {
-        return Some(MethodViolationCode::Generic);
+        let params = method.generics.types.get_slice(subst::FnSpace).iter()
+                                           .map(|param| param.name)
+                                           .collect();
+        return Some(MethodViolationCode::Generic(params));
     }
 
     None
@@ -21,10 +21,12 @@ use super::VtableClosureData;
 use super::VtableImplData;
 use super::util;
 
+use middle::def_id::DefId;
 use middle::infer;
 use middle::subst::Subst;
 use middle::ty::{self, ToPredicate, RegionEscape, HasTypeFlags, ToPolyTraitRef, Ty};
 use middle::ty::fold::{TypeFoldable, TypeFolder};
+use syntax::ast;
 use syntax::parse::token;
 use util::common::FN_OUTPUT_NAME;
 
@@ -65,6 +67,20 @@ struct ProjectionTyCandidateSet<'tcx> {
     ambiguous: bool
 }
 
+/// Why an impl of the trait did not provide the value of a projection.
+#[derive(Clone, Debug)]
+pub enum ProjectionImplRejection<'tcx> {
+    /// The impl is for another self type or other trait parameters.
+    HeaderMismatch,
+
+    /// The impl header matches, but a where-clause of the impl does not hold.
+    WhereClauseUnsatisfied,
+
+    /// The impl applies, but declares the associated type as this type,
+    /// in terms of the impl's own type parameters.
+    DefinesAs(Ty<'tcx>),
+}
+
 /// Evaluates constraints of the form:
 ///
 ///     for<...> <T as Trait>::U == V
@@ -543,6 +559,67 @@ fn assemble_candidates_from_param_env<'cx,'tcx>(
                                         candidate_set, env_predicates);
 }
 
+/// For each impl that might define the associated type of `obligation`,
+/// says why normalization did not take the associated type from it.
+/// Impls whose self type can be rejected without unification are skipped.
+pub fn projection_impl_rejections<'cx,'tcx>(
+    selcx: &mut SelectionContext<'cx,'tcx>,
+    obligation: &PolyProjectionObligation<'tcx>)
+    -> Vec<(DefId, ProjectionImplRejection<'tcx>)>
+{
+    let tcx = selcx.tcx();
+    let poly_trait_ref = obligation.predicate.to_poly_trait_ref();
+    let trait_obligation = obligation.with(poly_trait_ref.to_poly_trait_predicate());
+    let item_name = obligation.predicate.item_name();
+
+    let mut rejections = vec![];
+    tcx.lookup_trait_def(poly_trait_ref.def_id()).for_each_relevant_impl(
+        tcx,
+        poly_trait_ref.self_ty(),
+        |impl_def_id| {
+            let rejection = if !selcx.match_impl_header(impl_def_id, &trait_obligation) {
+                ProjectionImplRejection::HeaderMismatch
+            } else if !selcx.evaluate_impl(impl_def_id, &trait_obligation) {
+                ProjectionImplRejection::WhereClauseUnsatisfied
+            } else {
+                match impl_associated_type(tcx, impl_def_id, item_name) {
+                    Some(ty) => ProjectionImplRejection::DefinesAs(ty),
+                    None => return,
+                }
+            };
+            rejections.push((impl_def_id, rejection));
+        });
+    rejections
+}
+
+/// The type that the impl `impl_def_id` declares for the associated type
+/// `item_name`, falling back to the trait's default.
+fn impl_associated_type<'tcx>(tcx: &ty::ctxt<'tcx>,
+                              impl_def_id: DefId,
+                              item_name: ast::Name)
+                              -> Option<Ty<'tcx>>
+{
+    let impl_or_trait_items_map = tcx.impl_or_trait_items.borrow();
+    for impl_item in &tcx.impl_items.borrow()[&impl_def_id] {
+        if let ty::TypeTraitItem(ref assoc_ty) = impl_or_trait_items_map[&impl_item.def_id()] {
+            if assoc_ty.name == item_name {
+                return assoc_ty.ty;
+            }
+        }
+    }
+
+    let trait_def_id = match tcx.impl_trait_ref(impl_def_id) {
+        Some(trait_ref) => trait_ref.def_id,
+        None => return None,
+    };
+    tcx.trait_items(trait_def_id).iter().filter_map(|trait_item| {
+        match *trait_item {
+            ty::TypeTraitItem(ref assoc_ty) if assoc_ty.name == item_name => assoc_ty.ty,
+            _ => None,
+        }
+    }).next()
+}
+
 /// In the case of a nested projection like <<A as Foo>::FooT as Bar>::BarT, we may find
 /// that the definition of `Foo` has some clues:
 ///
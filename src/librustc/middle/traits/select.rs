@@ -22,11 +22,11 @@ use super::DerivedObligationCause;
 use super::project;
 use super::project::{normalize_with_depth, Normalized};
 use super::{PredicateObligation, TraitObligation, ObligationCause};
-use super::report_overflow_error;
+use super::{report_overflow_error, report_overflow_error_recoverable};
 use super::{ObligationCauseCode, BuiltinDerivedObligation, ImplDerivedObligation};
 use super::{SelectionError, Unimplemented, OutputTypeParameterMismatch};
 use super::{ObjectCastObligation, Obligation};
-use super::TraitNotObjectSafe;
+use super::{TraitNotObjectSafe, Overflow};
 use super::RFC1214Warning;
 use super::Selection;
 use super::SelectionResult;
@@ -50,6 +50,7 @@ use std::cell::RefCell;
 use std::fmt;
 use std::rc::Rc;
 use syntax::abi;
+use syntax::ast;
 use rustc_front::hir;
 use util::common::ErrorReported;
 use util::nodemap::FnvHashMap;
@@ -80,6 +81,13 @@ pub struct SelectionContext<'cx, 'tcx:'cx> {
     /// would satisfy it. This avoids crippling inference, basically.
 
     intercrate: bool,
+
+    /// If true, an obligation handed to `select` that has hit the
+    /// recursion limit is reported and fails with `Overflow`, rather
+    /// than aborting compilation. Only callers that report every
+    /// selection error, and can carry on after one, should set this;
+    /// overflows met in nested selections and evaluations always abort.
+    recover_from_overflow: bool,
 }
 
 // A stack that walks back up the stack frame.
@@ -250,6 +258,7 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
             infcx: infcx,
             freshener: infcx.freshener(),
             intercrate: false,
+            recover_from_overflow: false,
         }
     }
 
@@ -259,6 +268,17 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
             infcx: infcx,
             freshener: infcx.freshener(),
             intercrate: true,
+            recover_from_overflow: false,
+        }
+    }
+
+    pub fn recovering_from_overflow(infcx: &'cx InferCtxt<'cx, 'tcx>)
+                                    -> SelectionContext<'cx, 'tcx> {
+        SelectionContext {
+            infcx: infcx,
+            freshener: infcx.freshener(),
+            intercrate: false,
+            recover_from_overflow: true,
         }
     }
 
@@ -300,6 +320,25 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
         debug!("select({:?})", obligation);
         assert!(!obligation.predicate.has_escaping_regions());
 
+        if !self.recover_from_overflow {
+            return self.select_aborting_on_overflow(obligation);
+        }
+        if obligation.recursion_depth >= self.infcx.tcx.sess.recursion_limit.get() {
+            report_overflow_error_recoverable(self.infcx(), obligation);
+            return Err(Overflow);
+        }
+
+        // Selections made along the way, e.g. while normalizing the
+        // impl's where-clauses, may be speculative, so only this
+        // outermost one recovers.
+        self.recover_from_overflow = false;
+        let result = self.select_aborting_on_overflow(obligation);
+        self.recover_from_overflow = true;
+        result
+    }
+
+    fn select_aborting_on_overflow(&mut self, obligation: &TraitObligation<'tcx>)
+                                   -> SelectionResult<'tcx, Selection<'tcx>> {
         let stack = self.push_stack(TraitObligationStackList::empty(), obligation);
         match try!(self.candidate_from_obligation(&stack)) {
             None => {
@@ -607,6 +646,42 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
         }
     }
 
+    /// Checks only whether the header of the impl with id `impl_def_id`
+    /// unifies with `obligation`, ignoring the impl's where-clauses.
+    pub fn match_impl_header(&mut self,
+                             impl_def_id: DefId,
+                             obligation: &TraitObligation<'tcx>)
+                             -> bool
+    {
+        self.infcx.probe(|snapshot| {
+            self.match_impl(impl_def_id, obligation, snapshot).is_ok()
+        })
+    }
+
+    /// The impls and where-clauses that are candidates for the ambiguous
+    /// `obligation`, for error reporting; other kinds of candidate are
+    /// left out.
+    pub fn ambiguity_candidates(&mut self,
+                                obligation: &TraitObligation<'tcx>)
+                                -> (Vec<DefId>, Vec<ty::PolyTraitRef<'tcx>>)
+    {
+        let candidates = self.infcx.probe(|_| {
+            let stack = self.push_stack(TraitObligationStackList::empty(), obligation);
+            self.assemble_candidates(&stack).map(|candidates| candidates.vec)
+        }).unwrap_or(vec![]);
+
+        let mut impls = vec![];
+        let mut where_clauses = vec![];
+        for candidate in candidates {
+            match candidate {
+                ImplCandidate(impl_def_id) => impls.push(impl_def_id),
+                ParamCandidate(trait_ref) => where_clauses.push(trait_ref),
+                _ => {}
+            }
+        }
+        (impls, where_clauses)
+    }
+
     /// Evaluates whether the impl with id `impl_def_id` could be applied to the self type
     /// `obligation_self_ty`. This can be used either for trait or inherent impls.
     pub fn evaluate_impl(&mut self,
@@ -1893,13 +1968,22 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                                     types: ty::Binder<Vec<Ty<'tcx>>>)
                                     -> Vec<PredicateObligation<'tcx>>
     {
-        let derived_cause = match self.tcx().lang_items.to_builtin_kind(trait_def_id) {
-            Some(_) => {
-                self.derived_cause(obligation, BuiltinDerivedObligation)
-            },
-            None => {
-                self.derived_cause(obligation, ImplDerivedObligation)
+        let variant: fn(DerivedObligationCause<'tcx>) -> ObligationCauseCode<'tcx> =
+            match self.tcx().lang_items.to_builtin_kind(trait_def_id) {
+                Some(_) => BuiltinDerivedObligation,
+                None => ImplDerivedObligation,
+            };
+
+        // For an enum, the constituent types are the fields of all of
+        // its variants in order; remember which variant each came from.
+        let self_ty = self.infcx.shallow_resolve(obligation.predicate.skip_binder().self_ty());
+        let variant_names: Vec<ast::Name> = match self_ty.sty {
+            ty::TyEnum(def, _) => {
+                def.variants.iter()
+                            .flat_map(|v| v.fields.iter().map(move |_| v.name))
+                            .collect()
             }
+            _ => vec![],
         };
 
         // Because the types were potentially derived from
@@ -1924,7 +2008,10 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                  .collect();
 
         // For each type, produce a vector of resulting obligations
-        let obligations: Result<Vec<Vec<_>>, _> = bound_types.iter().map(|nested_ty| {
+        let obligations: Result<Vec<Vec<_>>, _> =
+                bound_types.iter().enumerate().map(|(i, nested_ty)| {
+            let parent_variant = variant_names.get(i).cloned();
+            let derived_cause = self.derived_cause(obligation, variant, parent_variant);
             self.infcx.commit_if_ok(|snapshot| {
                 let (skol_ty, skol_map) =
                     self.infcx().skolemize_late_bound_regions(nested_ty, snapshot);
@@ -2908,7 +2995,8 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
     #[allow(unused_comparisons)]
     fn derived_cause(&self,
                      obligation: &TraitObligation<'tcx>,
-                     variant: fn(DerivedObligationCause<'tcx>) -> ObligationCauseCode<'tcx>)
+                     variant: fn(DerivedObligationCause<'tcx>) -> ObligationCauseCode<'tcx>,
+                     parent_variant: Option<ast::Name>)
                      -> ObligationCause<'tcx>
     {
         /*!
@@ -2929,6 +3017,7 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                 ObligationCauseCode::RFC1214(ref base_code) => {
                     let derived_cause = DerivedObligationCause {
                         parent_trait_ref: obligation.predicate.to_poly_trait_ref(),
+                        parent_variant: parent_variant,
                         parent_code: base_code.clone(),
                     };
                     ObligationCauseCode::RFC1214(Rc::new(variant(derived_cause)))
@@ -2936,6 +3025,7 @@ impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
                 _ => {
                     let derived_cause = DerivedObligationCause {
                         parent_trait_ref: obligation.predicate.to_poly_trait_ref(),
+                        parent_variant: parent_variant,
                         parent_code: Rc::new(obligation.cause.code.clone())
                     };
                     variant(derived_cause)
@@ -3010,7 +3100,8 @@ impl<'tcx> EvaluationResult<'tcx> {
             EvaluatedToErr(TraitNotObjectSafe(_)) =>
                 true,
 
-            EvaluatedToErr(Unimplemented) =>
+            EvaluatedToErr(Unimplemented) |
+            EvaluatedToErr(Overflow) =>
                 false,
         }
     }
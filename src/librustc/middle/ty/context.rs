@@ -197,6 +197,11 @@ impl<'tcx> CommonTypes<'tcx> {
     }
 }
 
+/// Produces a fallback note for an unimplemented trait that has no
+/// `#[rustc_on_unimplemented]` attribute; see
+/// `ctxt::register_on_unimplemented_hook`.
+pub type OnUnimplementedHook = Rc<Fn(&ctxt, &TraitRef) -> Option<String>>;
+
 /// The data structure to keep track of all the information that typechecker
 /// generates so that so that it can be reused and doesn't have to be redone
 /// later on.
@@ -369,9 +374,27 @@ pub struct ctxt<'tcx> {
     /// fragmented data to the set of unfragmented pieces that
     /// constitute it.
     pub fragment_infos: RefCell<DefIdMap<Vec<ty::FragmentInfo>>>,
+
+    /// The distinct `(self type, trait)` pairs reported as unimplemented
+    /// so far, in the order they were first reported. Only collected
+    /// under `-Z trait-error-summary`.
+    pub unimplemented_traits: RefCell<Vec<(String, String)>>,
+
+    /// Fallback notes for unimplemented traits, by trait. The trait's own
+    /// `#[rustc_on_unimplemented]` attribute takes precedence.
+    pub on_unimplemented_hooks: RefCell<DefIdMap<OnUnimplementedHook>>,
 }
 
 impl<'tcx> ctxt<'tcx> {
+    /// Registers `hook` to produce the note for unimplemented-trait errors
+    /// about the trait `trait_def_id` that has no `#[rustc_on_unimplemented]`
+    /// attribute. Replaces any hook registered earlier for the same trait.
+    pub fn register_on_unimplemented_hook<F>(&self, trait_def_id: DefId, hook: F)
+        where F: Fn(&ctxt, &TraitRef) -> Option<String> + 'static
+    {
+        self.on_unimplemented_hooks.borrow_mut().insert(trait_def_id, Rc::new(hook));
+    }
+
     pub fn type_parameter_def(&self,
                               node_id: NodeId)
                               -> ty::TypeParameterDef<'tcx>
@@ -509,6 +532,8 @@ impl<'tcx> ctxt<'tcx> {
             custom_coerce_unsized_kinds: RefCell::new(DefIdMap()),
             cast_kinds: RefCell::new(NodeMap()),
             fragment_infos: RefCell::new(DefIdMap()),
+            unimplemented_traits: RefCell::new(Vec::new()),
+            on_unimplemented_hooks: RefCell::new(DefIdMap()),
        }, f)
     }
 }
@@ -280,7 +280,28 @@ impl<'a,'tcx> WfPredicates<'a,'tcx> {
     /// `self.out`.
     fn compute_trait_ref(&mut self, trait_ref: &ty::TraitRef<'tcx>) {
         let obligations = self.nominal_obligations(trait_ref.def_id, trait_ref.substs);
-        self.out.extend(obligations);
+
+        // Attribute the trait's supertraits to it as such, rather than
+        // as if they were ordinary where-clauses.
+        let supertrait_predicates =
+            self.infcx.tcx.lookup_super_predicates(trait_ref.def_id)
+                          .instantiate_supertrait(self.infcx.tcx, &ty::Binder(trait_ref.clone()))
+                          .predicates;
+        for obligation in obligations {
+            let supertrait_def_id = match obligation.predicate {
+                ty::Predicate::Trait(ref data) => Some(data.def_id()),
+                _ => None,
+            };
+            match supertrait_def_id {
+                Some(supertrait_def_id)
+                        if supertrait_predicates.contains(&obligation.predicate) => {
+                    let code = traits::SupertraitObligation(trait_ref.def_id, supertrait_def_id);
+                    let cause = self.cause(code);
+                    self.out.push(traits::Obligation { cause: cause, ..obligation });
+                }
+                _ => self.out.push(obligation),
+            }
+        }
 
         let cause = self.cause(traits::MiscObligation);
         self.out.extend(
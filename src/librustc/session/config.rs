@@ -594,6 +594,18 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
           "Force nonzeroing move optimization on"),
     keep_mtwt_tables: bool = (false, parse_bool,
           "Don't clear the resolution tables after analysis"),
+    gcc_style_trait_errors: bool = (false, parse_bool,
+          "Emit trait errors as single `file:line:col: error: msg [CODE]` lines"),
+    trait_error_summary: bool = (false, parse_bool,
+          "Summarize the distinct unimplemented traits after type checking"),
+    stable_trait_error_names: bool = (false, parse_bool,
+          "Rename inference and region variables in trait errors to `_0`, `'0`, ..."),
+    collapse_trait_errors: bool = (false, parse_bool,
+          "Report repeated trait errors for the same predicate once, with a count"),
+    related_trait_errors: bool = (false, parse_bool,
+          "Point trait errors derived from the same obligation at each other"),
+    feature_trait_warnings: Vec<String> = (Vec::new(), parse_list,
+          "Report trait errors of obligations introduced by these features as warnings"),
 }
 
 pub fn default_lib_output() -> CrateType {
@@ -41,6 +41,20 @@ pub fn lev_distance(me: &str, t: &str) -> usize {
     dcol[t_last + 1]
 }
 
+/// The candidate closest to `name` by edit distance, unless even that one
+/// is more than a third of `name`'s length away.
+pub fn closest_name<'a, I>(candidates: I, name: &str) -> Option<&'a str>
+    where I: IntoIterator<Item=&'a str>
+{
+    let max_distance = cmp::max(name.len(), 3) / 3;
+    candidates.into_iter()
+              .filter(|&candidate| candidate != name)
+              .map(|candidate| (lev_distance(name, candidate), candidate))
+              .filter(|&(distance, _)| distance <= max_distance)
+              .min_by(|&(distance, _)| distance)
+              .map(|(_, candidate)| candidate)
+}
+
 #[test]
 fn test_lev_distance() {
     use std::char::{ from_u32, MAX };
@@ -61,3 +75,11 @@ fn test_lev_distance() {
     assert_eq!(lev_distance(b, c), 1);
     assert_eq!(lev_distance(c, b), 1);
 }
+
+#[test]
+fn test_closest_name() {
+    let names = ["Self", "Type", "Rhs"];
+    assert_eq!(closest_name(names.iter().cloned(), "Tpe"), Some("Type"));
+    assert_eq!(closest_name(names.iter().cloned(), "Slf"), Some("Self"));
+    assert_eq!(closest_name(names.iter().cloned(), "Output"), None);
+}
@@ -22,14 +22,16 @@ use rustc_typeck::middle::region::CodeExtentData;
 use rustc_typeck::middle::resolve_lifetime;
 use rustc_typeck::middle::stability;
 use rustc_typeck::middle::subst;
-use rustc_typeck::middle::subst::Subst;
-use rustc_typeck::middle::ty::{self, Ty, RegionEscape};
+use rustc_typeck::middle::subst::{Subst, Substs};
+use rustc_typeck::middle::traits;
+use rustc_typeck::middle::ty::{self, Ty, RegionEscape, ToPredicate};
 use rustc_typeck::middle::ty::relate::TypeRelation;
 use rustc_typeck::middle::infer;
 use rustc_typeck::middle::infer::lub::Lub;
 use rustc_typeck::middle::infer::glb::Glb;
 use rustc_typeck::middle::infer::sub::Sub;
 use rustc::front::map as hir_map;
+use rustc_typeck::collect;
 use rustc::session::{self,config};
 use syntax::{abi, ast};
 use syntax::codemap;
@@ -38,6 +40,8 @@ use syntax::diagnostic::{Level, RenderSpan, Bug, Fatal, Error, Warning, Note, He
 use syntax::parse::token;
 use syntax::feature_gate::UnstableFeatures;
 
+use std::sync::{Arc, Mutex};
+
 use rustc_front::lowering::{lower_crate, LoweringContext};
 use rustc_front::hir;
 
@@ -94,6 +98,35 @@ impl Emitter for ExpectErrorEmitter {
     }
 }
 
+/// Records the notes emitted, ignoring other messages.
+struct NoteRecorder {
+    notes: Arc<Mutex<Vec<String>>>
+}
+
+impl Emitter for NoteRecorder {
+    fn emit(&mut self,
+            _cmsp: Option<(&codemap::CodeMap, Span)>,
+            msg: &str,
+            _: Option<&str>,
+            lvl: Level)
+    {
+        if lvl == Note {
+            self.notes.lock().unwrap().push(msg.to_string());
+        }
+    }
+
+    fn custom_emit(&mut self,
+                   _cm: &codemap::CodeMap,
+                   _sp: RenderSpan,
+                   msg: &str,
+                   lvl: Level)
+    {
+        if lvl == Note {
+            self.notes.lock().unwrap().push(msg.to_string());
+        }
+    }
+}
+
 fn errors(msgs: &[&str]) -> (Box<Emitter+Send>, usize) {
     let v = msgs.iter().map(|m| m.to_string()).collect();
     (box ExpectErrorEmitter { messages: v } as Box<Emitter+Send>, msgs.len())
@@ -180,7 +213,6 @@ impl<'a, 'tcx> Env<'a, 'tcx> {
             dscope);
     }
 
-    #[allow(dead_code)] // this seems like it could be useful, even if we don't use it now
     pub fn lookup_item(&self, names: &[String]) -> ast::NodeId {
         return match search_mod(self, &self.infcx.tcx.map.krate().module, 0, names) {
             Some(id) => id,
@@ -861,3 +893,125 @@ fn walk_ty_skip_subtree() {
         assert!(expected.is_empty());
     })
 }
+
+#[test]
+fn on_unimplemented_hook() {
+    let notes = Arc::new(Mutex::new(vec![]));
+    let emitter = box NoteRecorder { notes: notes.clone() } as Box<Emitter+Send>;
+    test_env("#![feature(no_core)] #![no_core] trait Dummy {}", (emitter, 1), |env| {
+        let tcx = env.infcx.tcx;
+        collect::collect_item_types(tcx);
+        let trait_def_id = tcx.map.local_def_id(env.lookup_item(&["Dummy".to_string()]));
+        tcx.register_on_unimplemented_hook(trait_def_id, |_, trait_ref| {
+            Some(format!("did you forget to import the `Dummy` impl for `{}`?",
+                         trait_ref.self_ty()))
+        });
+
+        let substs = Substs::new_trait(vec![], vec![], tcx.types.isize);
+        let trait_ref = ty::TraitRef::new(trait_def_id, tcx.mk_substs(substs));
+        let obligation = traits::Obligation::new(traits::ObligationCause::dummy(),
+                                                 trait_ref.to_predicate());
+        traits::report_selection_error(env.infcx, &obligation, &traits::Unimplemented);
+    });
+    let notes = notes.lock().unwrap();
+    assert!(notes.iter().any(|note| {
+        note == "did you forget to import the `Dummy` impl for `isize`?"
+    }), "hook note not emitted: {:?}", *notes);
+}
+
+#[test]
+fn selection_error_report() {
+    test_env("#![feature(no_core)] #![no_core] trait Dummy {}", errors(&[]), |env| {
+        let tcx = env.infcx.tcx;
+        collect::collect_item_types(tcx);
+        let trait_def_id = tcx.map.local_def_id(env.lookup_item(&["Dummy".to_string()]));
+        tcx.register_on_unimplemented_hook(trait_def_id, |_, trait_ref| {
+            Some(format!("did you forget to import the `Dummy` impl for `{}`?",
+                         trait_ref.self_ty()))
+        });
+
+        let substs = Substs::new_trait(vec![], vec![], tcx.types.isize);
+        let trait_ref = ty::TraitRef::new(trait_def_id, tcx.mk_substs(substs));
+        let obligation = traits::Obligation::new(traits::ObligationCause::dummy(),
+                                                 trait_ref.to_predicate());
+        let report = traits::build_selection_error_report(env.infcx, &obligation,
+                                                          &traits::Unimplemented).unwrap();
+        assert_eq!(report.code, "E0277");
+        assert!(!report.is_warning);
+        assert!(report.message.ends_with("is not implemented for the type `isize`"),
+                "unexpected message: {}", report.message);
+        assert_eq!(report.children, vec![traits::TraitErrorChild::Note(
+            DUMMY_SP, "did you forget to import the `Dummy` impl for `isize`?".to_string())]);
+    });
+}
+
+#[test]
+fn selection_error_report_collects_attribute_errors() {
+    // Building the report must not emit the error in the attribute.
+    test_env("#![feature(no_core, on_unimplemented)] #![no_core] \
+              #[rustc_on_unimplemented = \"`{Missing}` is required\"] trait Dummy {}",
+             errors(&[]), |env| {
+        let tcx = env.infcx.tcx;
+        collect::collect_item_types(tcx);
+        let trait_def_id = tcx.map.local_def_id(env.lookup_item(&["Dummy".to_string()]));
+
+        let substs = Substs::new_trait(vec![], vec![], tcx.types.isize);
+        let trait_ref = ty::TraitRef::new(trait_def_id, tcx.mk_substs(substs));
+        let obligation = traits::Obligation::new(traits::ObligationCause::dummy(),
+                                                 trait_ref.to_predicate());
+        let report = traits::build_selection_error_report(env.infcx, &obligation,
+                                                          &traits::Unimplemented).unwrap();
+        assert_eq!(report.code, "E0277");
+        assert!(report.children.is_empty());
+        let codes: Vec<_> = report.attribute_errors.iter().map(|error| error.code).collect();
+        assert_eq!(codes, vec!["E0272"]);
+    });
+}
+
+#[test]
+fn same_span_trait_errors_in_stable_order() {
+    test_env("#![feature(no_core)] #![no_core] trait Dummy {}", errors(&[]), |env| {
+        let tcx = env.infcx.tcx;
+        collect::collect_item_types(tcx);
+        let trait_def_id = tcx.map.local_def_id(env.lookup_item(&["Dummy".to_string()]));
+        let error = |self_ty, code| {
+            let substs = Substs::new_trait(vec![], vec![], self_ty);
+            let trait_ref = ty::TraitRef::new(trait_def_id, tcx.mk_substs(substs));
+            let obligation = traits::Obligation::new(traits::ObligationCause::dummy(),
+                                                     trait_ref.to_predicate());
+            traits::FulfillmentError { obligation: obligation, code: code }
+        };
+
+        // All at the same span: ordered by error code, then by predicate.
+        let unimplemented = || traits::CodeSelectionError(traits::Unimplemented);
+        let errors = vec![error(tcx.types.isize, traits::CodeAmbiguity),
+                          error(tcx.types.isize, unimplemented()),
+                          error(tcx.types.i32, unimplemented())];
+        let sorted: Vec<_> = traits::in_source_order(env.infcx, &errors).iter().map(|error| {
+            format!("{:?}", error)
+        }).collect();
+        let expected: Vec<_> = [2, 1, 0].iter().map(|&i| format!("{:?}", errors[i])).collect();
+        assert_eq!(sorted, expected);
+    });
+}
+
+#[test]
+fn positional_argument_spans() {
+    test_env("#![feature(no_core, on_unimplemented)] #![no_core] \
+              #[rustc_on_unimplemented = \"{{x}} {Self} is {0:?}, not {}\"] trait Dummy {}",
+             errors(&[]), |env| {
+        let tcx = env.infcx.tcx;
+        let item = tcx.map.expect_item(env.lookup_item(&["Dummy".to_string()]));
+        let attr_span = item.attrs[0].span;
+        let istring = "{{x}} {Self} is {0:?}, not {}";
+        let codemap = tcx.sess.codemap();
+        let snippet = |index| {
+            let span = traits::positional_argument_span(codemap, attr_span, istring, index);
+            codemap.span_to_snippet(span).unwrap()
+        };
+        assert_eq!(snippet(0), "{0:?}");
+        assert_eq!(snippet(1), "{}");
+        // Past the last positional argument, the whole attribute is kept.
+        assert_eq!(snippet(2), codemap.span_to_snippet(attr_span).unwrap());
+    });
+}
@@ -269,6 +269,14 @@ impl LateLintPass for UnusedAttributes {
         }
 
         if !attr::is_used(attr) {
+            // Only traits consult `#[rustc_on_unimplemented]`, so an unused
+            // one was put on something else.
+            if attr.name() == "rustc_on_unimplemented" {
+                cx.span_lint(UNUSED_ATTRIBUTES, attr.span,
+                             "`#[rustc_on_unimplemented]` has no effect on anything \
+                              but a trait, and is ignored here");
+                return;
+            }
             cx.span_lint(UNUSED_ATTRIBUTES, attr.span, "unused attribute");
             // Is it a builtin attribute that must be used at the crate level?
             let known_crate = KNOWN_ATTRIBUTES.iter().find(|&&(name, ty, _)| {
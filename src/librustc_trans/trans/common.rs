@@ -1021,6 +1021,13 @@ pub fn fulfill_obligation<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
                 span,
                 "reached the recursion limit during monomorphization (selection ambiguity)");
         }
+        Err(traits::Overflow) => {
+            // `SelectionContext::new` aborts on overflow rather than
+            // returning this, but hitting the recursion limit is no bug.
+            ccx.sess().span_fatal(
+                span,
+                "reached the recursion limit during monomorphization (selection overflow)");
+        }
         Err(e) => {
             tcx.sess.span_bug(
                 span,
@@ -14,6 +14,7 @@ use middle::traits;
 use middle::ty::{self};
 use middle::subst::{self, Subst, Substs, VecPerParamSpace};
 
+use std::rc::Rc;
 use syntax::ast;
 use syntax::codemap::Span;
 
@@ -219,6 +220,14 @@ pub fn compare_impl_method<'tcx>(tcx: &ty::ctxt<'tcx>,
     let trait_pred = trait_bounds.predicates.split();
     let impl_pred = impl_bounds.predicates.split();
 
+    // The bounds that appear on the impl method but not on the trait
+    // method; errors for any of them report the whole set.
+    let extra_impl_preds: Rc<Vec<_>> = Rc::new(
+        impl_pred.fns.iter()
+                     .filter(|p| !trait_pred.fns.contains(p))
+                     .cloned()
+                     .collect());
+
     // This is the only tricky bit of the new way we check implementation methods
     // We need to build a set of predicates where only the FnSpace bounds
     // are from the trait and we assume all other bounds from the implementation
@@ -254,7 +263,8 @@ pub fn compare_impl_method<'tcx>(tcx: &ty::ctxt<'tcx>,
         let cause = traits::ObligationCause {
             span: impl_m_span,
             body_id: impl_m_body_id,
-            code: traits::ObligationCauseCode::CompareImplMethodObligation
+            code: traits::ObligationCauseCode::CompareImplMethodObligation(
+                trait_m.def_id, extra_impl_preds.clone())
         };
 
         fulfillment_cx.register_predicate_obligation(
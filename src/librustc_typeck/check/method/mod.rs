@@ -23,9 +23,12 @@ use middle::infer;
 
 use syntax::ast;
 use syntax::codemap::Span;
+use syntax::parse::token;
 
 use rustc_front::hir;
 
+use std::rc::Rc;
+
 pub use self::MethodError::*;
 pub use self::CandidateSource::*;
 
@@ -142,6 +145,16 @@ pub fn lookup_in_trait<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
                              0, false, self_ty, opt_input_types)
 }
 
+/// Whether `trait_def_id` is one of the traits that `a <op>= b` is
+/// overloaded through.
+fn is_op_assign_trait(tcx: &ty::ctxt, trait_def_id: DefId) -> bool {
+    let items = &tcx.lang_items;
+    [items.add_assign_trait(), items.sub_assign_trait(), items.mul_assign_trait(),
+     items.div_assign_trait(), items.rem_assign_trait(), items.bitxor_assign_trait(),
+     items.bitand_assign_trait(), items.bitor_assign_trait(), items.shl_assign_trait(),
+     items.shr_assign_trait()].contains(&Some(trait_def_id))
+}
+
 /// `lookup_in_trait_adjusted` is used for overloaded operators. It does a very narrow slice of
 /// what the normal probe/confirm path does. In particular, it doesn't really do any probing: it
 /// simply constructs an obligation for a particular trait with the given self-type and checks
@@ -251,8 +264,18 @@ pub fn lookup_in_trait_adjusted<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
     // any late-bound regions appearing in its bounds.
     let method_bounds = fcx.instantiate_bounds(span, trait_ref.substs, &method_ty.predicates);
     assert!(!method_bounds.has_escaping_regions());
+
+    // The `*Assign` traits behind overloaded augmented assignment come
+    // with the `augmented_assignments` feature, so failures of their
+    // bounds are attributed to it.
+    let cause_code = if is_op_assign_trait(tcx, trait_def_id) {
+        traits::FeatureMigration(token::intern("augmented_assignments"),
+                                 Rc::new(traits::MiscObligation))
+    } else {
+        traits::MiscObligation
+    };
     fcx.add_obligations_for_parameters(
-        traits::ObligationCause::misc(span, fcx.body_id),
+        traits::ObligationCause::new(span, fcx.body_id, cause_code),
         &method_bounds);
 
     // Also register an obligation for the method type being well-formed.
@@ -21,7 +21,7 @@ use middle::def;
 use middle::def_id::DefId;
 use middle::lang_items::FnOnceTraitLangItem;
 use middle::subst::Substs;
-use middle::traits::{Obligation, SelectionContext};
+use middle::traits::{self, Obligation, SelectionContext};
 use metadata::{csearch, cstore, decoder};
 
 use syntax::ast;
@@ -152,7 +152,15 @@ pub fn report_error<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
             span_err!(fcx.sess(), span, E0034,
                       "multiple applicable items in scope");
 
+            let trait_sources: Vec<DefId> = sources.iter().filter_map(|source| {
+                match *source {
+                    CandidateSource::TraitSource(trait_did) => Some(trait_did),
+                    CandidateSource::ImplSource(_) => None,
+                }
+            }).collect();
+            suggest_inherent_or_trait_disambiguation(fcx, span, item_name, &sources);
             report_candidates(fcx, span, item_name, sources);
+            suggest_supertrait_disambiguation(fcx, span, rcvr_ty, item_name, trait_sources);
         }
 
         MethodError::ClosureAmbiguity(trait_def_id) => {
@@ -221,6 +229,114 @@ pub fn report_error<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
             }
         }
     }
+
+    /// When an inherent method and a trait method are both candidates
+    /// (e.g. an inherent impl on an object type whose trait has a method
+    /// of the same name), show the paths that call each of them.
+    fn suggest_inherent_or_trait_disambiguation(fcx: &FnCtxt,
+                                                span: Span,
+                                                item_name: ast::Name,
+                                                sources: &[CandidateSource]) {
+        let tcx = fcx.tcx();
+        let mut inherent_impl = None;
+        let mut trait_did = None;
+        for source in sources {
+            match *source {
+                CandidateSource::ImplSource(impl_did) => {
+                    match tcx.impl_trait_ref(impl_did) {
+                        None => inherent_impl = inherent_impl.or(Some(impl_did)),
+                        Some(trait_ref) => trait_did = trait_did.or(Some(trait_ref.def_id)),
+                    }
+                }
+                CandidateSource::TraitSource(did) => trait_did = trait_did.or(Some(did)),
+            }
+        }
+        let (impl_did, trait_did) = match (inherent_impl, trait_did) {
+            (Some(impl_did), Some(trait_did)) => (impl_did, trait_did),
+            _ => return,
+        };
+
+        let impl_ty = check::impl_self_ty(fcx, span, impl_did).ty;
+        fcx.sess().fileline_help(
+            span,
+            &format!("the inherent method `{}` of `{}` and the method of the trait `{}` both \
+                      apply; call `<{}>::{}(...)` for the inherent one or `{}::{}(...)` \
+                      for the trait's",
+                     item_name,
+                     impl_ty,
+                     tcx.item_path_str(trait_did),
+                     impl_ty,
+                     item_name,
+                     tcx.item_path_str(trait_did),
+                     item_name));
+    }
+
+    /// When the ambiguous methods come from two or more supertraits of a
+    /// single bound on the receiver (e.g. `T: Sub` where `Sub: A + B` and
+    /// both `A` and `B` define the method), say so and show the
+    /// fully-qualified call that picks one of them.
+    fn suggest_supertrait_disambiguation<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
+                                                   span: Span,
+                                                   rcvr_ty: Ty<'tcx>,
+                                                   item_name: ast::Name,
+                                                   mut trait_sources: Vec<DefId>) {
+        let tcx = fcx.tcx();
+        trait_sources.sort();
+        trait_sources.dedup();
+        if trait_sources.len() < 2 {
+            return;
+        }
+
+        let mut self_ty = rcvr_ty;
+        while let ty::TyRef(_, mt) = self_ty.sty {
+            self_ty = mt.ty;
+        }
+
+        let mut bounds: Vec<DefId> =
+            fcx.inh.infcx.parameter_environment.caller_bounds.iter()
+                .filter_map(|predicate| match *predicate {
+                    ty::Predicate::Trait(ref data) if data.0.self_ty() == self_ty => {
+                        Some(data.def_id())
+                    }
+                    _ => None,
+                }).collect();
+        if let ty::TyTrait(ref data) = self_ty.sty {
+            bounds.push(data.principal_def_id());
+        }
+
+        for bound in bounds {
+            if trait_sources.contains(&bound) {
+                continue;
+            }
+            let supertraits: Vec<DefId> = traits::supertrait_def_ids(tcx, bound).collect();
+            if !trait_sources.iter().all(|did| supertraits.contains(did)) {
+                continue;
+            }
+
+            // "`A` and `B`", "`A`, `B` and `C`", ...
+            let list = |items: Vec<String>, last: &str| {
+                let (init, tail) = items.split_at(items.len() - 1);
+                format!("{} {} {}", init.join(", "), last, tail[0])
+            };
+            let trait_names: Vec<String> = trait_sources.iter()
+                .map(|&did| format!("`{}`", tcx.item_path_str(did)))
+                .collect();
+            let calls: Vec<String> = trait_sources.iter()
+                .map(|&did| format!("`<{} as {}>::{}(...)`",
+                                    self_ty, tcx.item_path_str(did), item_name))
+                .collect();
+            fcx.sess().fileline_help(
+                span,
+                &format!("{} are {} supertraits of `{}` and each defines `{}`; \
+                          use fully-qualified syntax to pick one: {}",
+                         list(trait_names, "and"),
+                         if trait_sources.len() == 2 { "both" } else { "all" },
+                         tcx.item_path_str(bound),
+                         item_name,
+                         list(calls, "or")));
+            return;
+        }
+    }
 }
 
 
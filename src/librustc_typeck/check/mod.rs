@@ -110,7 +110,7 @@ use TypeAndSubsts;
 use lint;
 use util::common::{block_query, ErrorReported, indenter, loop_query};
 use util::nodemap::{DefIdMap, FnvHashMap, NodeMap};
-use util::lev_distance::lev_distance;
+use util::lev_distance::{closest_name, lev_distance};
 
 use std::cell::{Cell, Ref, RefCell};
 use std::collections::{HashSet};
@@ -310,8 +310,12 @@ impl<'a, 'tcx> Inherited<'a, 'tcx> {
            param_env: ty::ParameterEnvironment<'a, 'tcx>)
            -> Inherited<'a, 'tcx> {
 
+        let infcx = infer::new_infer_ctxt(tcx, tables, Some(param_env), true);
+        // Every fulfillment error in a fn body is reported, so an overflow
+        // need not stop type checking there.
+        infcx.fulfillment_cx.borrow_mut().recover_from_overflow = true;
         Inherited {
-            infcx: infer::new_infer_ctxt(tcx, tables, Some(param_env), true),
+            infcx: infcx,
             locals: RefCell::new(NodeMap()),
             tables: tables,
             fn_sig_map: RefCell::new(NodeMap()),
@@ -408,6 +412,7 @@ pub fn check_wf_old(ccx: &CrateCtxt) {
 
     // If types are not well-formed, it leads to all manner of errors
     // downstream, so stop reporting errors at this point.
+    traits::report_unimplemented_trait_summary(ccx.tcx);
     ccx.tcx.sess.abort_if_errors();
 }
 
@@ -418,6 +423,7 @@ pub fn check_wf_new(ccx: &CrateCtxt) {
 
     // If types are not well-formed, it leads to all manner of errors
     // downstream, so stop reporting errors at this point.
+    traits::report_unimplemented_trait_summary(ccx.tcx);
     ccx.tcx.sess.abort_if_errors();
 }
 
@@ -433,6 +439,7 @@ pub fn check_item_bodies(ccx: &CrateCtxt) {
     let mut visit = CheckItemBodiesVisitor { ccx: ccx };
     visit::walk_crate(&mut visit, krate);
 
+    traits::report_unimplemented_trait_summary(ccx.tcx);
     ccx.tcx.sess.abort_if_errors();
 }
 
@@ -836,36 +843,39 @@ fn check_trait_fn_not_const<'a,'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
 fn check_trait_on_unimplemented<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
                                generics: &hir::Generics,
                                item: &hir::Item) {
-    if let Some(ref attr) = item.attrs.iter().find(|a| {
+    let attrs: Vec<_> = item.attrs.iter().filter(|a| {
         a.check_name("rustc_on_unimplemented")
-    }) {
+    }).collect();
+    if let Some(list_attr) = attrs.iter().find(|a| a.meta_item_list().is_some()) {
+        if attrs.iter().any(|a| a.value_str().is_some()) {
+            span_err!(ccx.tcx.sess, list_attr.span, E0516,
+                      "the `#[rustc_on_unimplemented = \"...\"]` and \
+                       `#[rustc_on_unimplemented(...)]` forms cannot be combined, \
+                       eg use `#[rustc_on_unimplemented(note = \"foo\", label = \"bar\")]`");
+            return;
+        }
+    }
+    // Each attribute contributes its own messages, so check all of them.
+    for attr in &attrs {
         if let Some(ref istring) = attr.value_str() {
-            let parser = Parser::new(&istring);
-            let types = &*generics.ty_params;
-            for token in parser {
-                match token {
-                    Piece::String(_) => (), // Normal string, no need to check it
-                    Piece::NextArgument(a) => match a.position {
-                        // `{Self}` is allowed
-                        Position::ArgumentNamed(s) if s == "Self" => (),
-                        // So is `{A}` if A is a type parameter
-                        Position::ArgumentNamed(s) => match types.iter().find(|t| {
-                            t.name.as_str() == s
-                        }) {
-                            Some(_) => (),
-                            None => {
-                                span_err!(ccx.tcx.sess, attr.span, E0230,
-                                                 "there is no type parameter \
-                                                          {} on trait {}",
-                                                           s, item.name);
-                            }
-                        },
-                        // `{:1}` and `{}` are not to be used
-                        Position::ArgumentIs(_) | Position::ArgumentNext => {
-                            span_err!(ccx.tcx.sess, attr.span, E0231,
-                                                  "only named substitution \
-                                                   parameters are allowed");
-                        }
+            check_on_unimplemented_format(ccx, generics, item, attr.span, istring);
+        } else if let Some(sub_items) = attr.meta_item_list() {
+            for sub_item in sub_items {
+                match (&sub_item.name()[..], sub_item.value_str()) {
+                    ("message", Some(ref istring)) |
+                    ("note", Some(ref istring)) |
+                    ("help", Some(ref istring)) |
+                    ("label", Some(ref istring)) => {
+                        check_on_unimplemented_format(ccx, generics, item, attr.span, istring);
+                    }
+                    ("on", None) if sub_item.meta_item_list().is_some() => {
+                        check_on_unimplemented_guard(ccx, generics, item, sub_item);
+                    }
+                    _ => {
+                        span_err!(ccx.tcx.sess, sub_item.span, E0232,
+                                  "each item of this attribute must be `message`, `note`, \
+                                   `help` or `label` with a value, eg `note = \"foo\"`, \
+                                   or an `on(...)` guard");
                     }
                 }
             }
@@ -877,6 +887,106 @@ fn check_trait_on_unimplemented<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
     }
 }
 
+/// Checks that the `on(...)` guard of a `#[rustc_on_unimplemented]`
+/// attribute only tests `Self` and the trait's type parameters, each
+/// against a type written as a string, eg `on(Self = "{integer}")`.
+fn check_on_unimplemented_guard<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
+                                          generics: &hir::Generics,
+                                          item: &hir::Item,
+                                          guard: &ast::MetaItem) {
+    for condition in guard.meta_item_list().unwrap() {
+        let name = condition.name();
+        if condition.value_str().is_none() {
+            span_err!(ccx.tcx.sess, condition.span, E0232,
+                      "each condition of `on(...)` must compare a type parameter \
+                       with a type, eg `on(Self = \"{{integer}}\")`");
+        } else if &name[..] != "Self" &&
+                  !generics.ty_params.iter().any(|t| t.name.as_str() == &name[..]) {
+            span_err!(ccx.tcx.sess, condition.span, E0230,
+                      "there is no type parameter {} on trait {}",
+                      name, item.name);
+            suggest_on_unimplemented_param(ccx, generics, condition.span, &name, false);
+        }
+    }
+}
+
+/// Helps with a misspelt type parameter in a `#[rustc_on_unimplemented]`
+/// attribute by naming the closest one the trait does have, written as
+/// a substitution (`{A}`) if `in_format` and as a bare name otherwise.
+fn suggest_on_unimplemented_param(ccx: &CrateCtxt,
+                                  generics: &hir::Generics,
+                                  span: Span,
+                                  name: &str,
+                                  in_format: bool) {
+    let mut params = vec![token::intern_and_get_ident("Self")];
+    params.extend(generics.ty_params.iter().map(|t| t.name.as_str()));
+    if let Some(param) = closest_name(params.iter().map(|p| &p[..]), name) {
+        let param = if in_format { format!("{{{}}}", param) } else { param.to_string() };
+        ccx.tcx.sess.span_help(span, &format!("did you mean `{}`?", param));
+    }
+}
+
+/// Checks that a `#[rustc_on_unimplemented]` string only substitutes
+/// `Self`, the trait's type parameters and its associated types.
+fn check_on_unimplemented_format<'a, 'tcx>(ccx: &CrateCtxt<'a, 'tcx>,
+                                           generics: &hir::Generics,
+                                           item: &hir::Item,
+                                           span: Span,
+                                           istring: &str) {
+    let mut parser = Parser::new(istring);
+    let types = &*generics.ty_params;
+    let trait_def = ccx.tcx.lookup_trait_def(ccx.tcx.map.local_def_id(item.id));
+    let mut positional = 0;
+    for token in &mut parser {
+        match token {
+            // Normal string, including `{{` and `}}`, no need to check it
+            Piece::String(_) => (),
+            Piece::NextArgument(a) => match a.position {
+                // `{Self}` is allowed
+                Position::ArgumentNamed(s) if s == "Self" => (),
+                // So is `{A}` if A is a type parameter or associated type
+                Position::ArgumentNamed(s) => match types.iter().find(|t| {
+                    t.name.as_str() == s
+                }) {
+                    Some(_) => (),
+                    None if trait_def.associated_type_names.iter()
+                                     .any(|name| name.as_str() == s) => (),
+                    None => {
+                        span_err!(ccx.tcx.sess, span, E0230,
+                                         "there is no type parameter \
+                                                  {} on trait {}",
+                                                   s, item.name);
+                        suggest_on_unimplemented_param(ccx, generics, span, s, true);
+                    }
+                },
+                // `{0}` and `{}` are not to be used
+                Position::ArgumentIs(i) => {
+                    let arg_span = traits::positional_argument_span(ccx.tcx.sess.codemap(),
+                                                                    span, istring, positional);
+                    positional += 1;
+                    span_err!(ccx.tcx.sess, arg_span, E0231,
+                              "positional substitution parameter `{{{}}}` is not allowed; \
+                               name a type parameter instead, eg `{{Self}}`",
+                              i);
+                }
+                Position::ArgumentNext => {
+                    let arg_span = traits::positional_argument_span(ccx.tcx.sess.codemap(),
+                                                                    span, istring, positional);
+                    positional += 1;
+                    span_err!(ccx.tcx.sess, arg_span, E0231,
+                                          "only named substitution \
+                                           parameters are allowed");
+                }
+            }
+        }
+    }
+    for err in &parser.errors {
+        span_err!(ccx.tcx.sess, span, E0231,
+                  "invalid format string: {}; write `{{{{` and `}}}}` for literal braces",
+                  err);
+    }
+}
+
 /// Type checks a method body.
 ///
 /// # Parameters
@@ -268,7 +268,16 @@ impl<'ccx, 'tcx> CheckTypeWellFormedVisitor<'ccx, 'tcx> {
                 traits::normalize(selcx, cause.clone(), &predicates)
             };
             for predicate in predicates.value.predicates {
-                fcx.register_predicate(traits::Obligation::new(cause.clone(), predicate));
+                let cause = match predicate {
+                    ty::Predicate::Trait(ref data) => {
+                        traits::ObligationCause::new(
+                            item.span,
+                            fcx.body_id,
+                            traits::SupertraitObligation(trait_ref.def_id, data.def_id()))
+                    }
+                    _ => cause.clone(),
+                };
+                fcx.register_predicate(traits::Obligation::new(cause, predicate));
             }
             for obligation in predicates.obligations {
                 fcx.register_predicate(obligation);
@@ -3405,5 +3405,6 @@ register_diagnostics! {
     E0399, // trait items need to be implemented because the associated
            // type `{}` was overridden
     E0436, // functional record update requires a struct
-    E0513  // no type for local variable ..
+    E0513, // no type for local variable ..
+    E0516  // both forms of `#[rustc_on_unimplemented]` on one trait
 }
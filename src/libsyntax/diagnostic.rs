@@ -49,6 +49,11 @@ pub enum RenderSpan {
     /// A FileLine renders with just a line for the message prefixed
     /// by file:linenum.
     FileLine(Span),
+
+    /// A GccStyle renders as a single line prefixed by
+    /// file:linenum:colnum, with a 1-based column as GCC and Clang
+    /// print it, so that tools which parse their output can read it.
+    GccStyle(Span),
 }
 
 impl RenderSpan {
@@ -57,7 +62,8 @@ impl RenderSpan {
             FullSpan(s) |
             Suggestion(s, _) |
             EndSpan(s) |
-            FileLine(s) =>
+            FileLine(s) |
+            GccStyle(s) =>
                 s
         }
     }
@@ -171,6 +177,21 @@ impl SpanHandler {
     pub fn fileline_help(&self, sp: Span, msg: &str) {
         self.handler.custom_emit(&self.cm, FileLine(sp), msg, Help);
     }
+    /// Prints out a one-line message in the format GCC and Clang use.
+    ///
+    /// See `diagnostic::RenderSpan::GccStyle` for more information.
+    pub fn gcc_style(&self, sp: Span, msg: &str, code: Option<&str>, lvl: Level) {
+        match code {
+            Some(code) => {
+                self.handler.custom_emit(&self.cm, GccStyle(sp),
+                                         &format!("{} [{}]", msg, code), lvl)
+            }
+            None => self.handler.custom_emit(&self.cm, GccStyle(sp), msg, lvl),
+        }
+        if lvl == Error {
+            self.handler.bump_err_count();
+        }
+    }
     pub fn span_bug(&self, sp: Span, msg: &str) -> ! {
         self.handler.emit(Some((&self.cm, sp)), msg, Bug);
         panic!(ExplicitBug);
@@ -434,6 +455,10 @@ impl EmitterWriter {
         } else if let EndSpan(_) = rsp {
             let span_end = Span { lo: sp.hi, hi: sp.hi, expn_id: sp.expn_id};
             cm.span_to_string(span_end)
+        } else if let GccStyle(_) = rsp {
+            // The codemap's columns are 0-based.
+            let loc = cm.lookup_char_pos(sp.lo);
+            format!("{}:{}:{}:", loc.file.name, loc.line, loc.col.to_usize() + 1)
         } else {
             cm.span_to_string(sp)
         };
@@ -453,7 +478,7 @@ impl EmitterWriter {
                 try!(self.highlight_suggestion(cm, sp, suggestion));
                 try!(self.print_macro_backtrace(cm, sp));
             }
-            FileLine(..) => {
+            FileLine(..) | GccStyle(..) => {
                 // no source text in this case!
             }
         }
@@ -0,0 +1,30 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Test that an obligation with no inference variables left that still
+// matches more than one candidate is reported as an error rather than
+// an ICE. Where clauses that differ only in their regions all match
+// (see #21974).
+
+trait Foo {}
+
+fn need<U: Foo>() {}
+
+fn foo<'a, 'b, T>(_: &'a T, _: &'b T)
+    where &'a T: Foo,
+          &'b T: Foo
+{
+    need::<&'a T>();
+    //~^ ERROR multiple applicable implementations found for `&'a T : Foo`
+    //~| HELP annotate the types involved so that only one implementation applies
+}
+
+fn main() {}
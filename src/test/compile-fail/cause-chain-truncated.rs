@@ -0,0 +1,25 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Test that a long chain of "required because ..." notes is cut short,
+// keeping the innermost and outermost types.
+
+struct W<T>(T);
+
+fn is_send<T: Send>() {}
+
+fn main() {
+    is_send::<W<W<W<W<W<W<W<W<W<W<W<W<*const u8>>>>>>>>>>>>>();
+    //~^ ERROR the trait `core::marker::Send` is not implemented for the type `*const u8`
+    //~| NOTE required because it appears within the type `W<*const u8>`
+    //~| NOTE ...and 2 more
+    //~| NOTE required because it appears within the type `W<W<W<W<W<W<W<W<W<W<W<W<*const u8>>>>>>>>>>>>`
+}
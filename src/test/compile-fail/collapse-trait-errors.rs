@@ -0,0 +1,23 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// compile-flags: -Z collapse-trait-errors
+
+// Test that the same unsatisfied bound at several spans is reported
+// once, with a note for the other spans.
+
+fn f<X: ?Sized>(a: Box<X>, b: Box<X>) {
+    let (y, z) = (*a, *b);
+    //~^ ERROR the trait `core::marker::Sized` is not implemented for the type `X`
+    //~| NOTE is also required at
+}
+
+fn main() {}
@@ -0,0 +1,28 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that a failing auto-trait bound names the enum variant whose
+// payload is responsible.
+
+use std::rc::Rc;
+
+enum Message {
+    Quit,
+    Text(String),
+    Shared(Rc<u8>),
+}
+
+fn is_send<T: Send>() {}
+
+fn main() {
+    is_send::<Message>();
+    //~^ ERROR the trait `core::marker::Send` is not implemented for the type `alloc::rc::Rc<u8>`
+    //~| NOTE required because it appears within the variant `Shared` of the type `Message`
+}
@@ -17,6 +17,5 @@ fn main() {
     // extern functions are extern "C" fn
     let _x: extern "C" fn() = f; // OK
     is_fn(f);
-    //~^ ERROR the trait `core::ops::Fn<()>` is not implemented for the type `extern "C" fn()
-    //~| ERROR the trait `core::ops::FnOnce<()>` is not implemented for the type `extern "C" fn()
+    //~^ ERROR does not satisfy the following bounds: `core::ops::Fn<()>`, `core::ops::FnOnce<()>`
 }
@@ -0,0 +1,38 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// ignore-tidy-linelength
+// compile-flags: -Z feature-trait-warnings=augmented_assignments
+
+// Test that a bound failing through an overloaded augmented assignment
+// is only a warning when its feature is listed in
+// `-Z feature-trait-warnings`.
+
+#![feature(augmented_assignments, op_assign_traits, rustc_attrs)]
+#![allow(dead_code)]
+
+use std::ops::AddAssign;
+
+struct Counter;
+
+impl<T: Copy> AddAssign<T> for Counter {
+    fn add_assign(&mut self, _: T) {}
+}
+
+fn add_string(mut counter: Counter) {
+    counter += String::new();
+    //~^ WARN the trait `core::marker::Copy` is not implemented for the type `collections::string::String`
+    //~| NOTE this is a warning only because `-Z feature-trait-warnings` lists the feature `augmented_assignments`
+}
+
+#[rustc_error]
+fn main() { //~ ERROR compilation successful
+}
@@ -0,0 +1,23 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Test that a closure passed where an `Fn` bound expects other argument
+// types is reported in terms of the argument types.
+
+fn call<F: Fn(u32) -> i32>(f: F) -> i32 {
+    f(0)
+}
+
+fn main() {
+    call(|x: i32| x);
+    //~^ ERROR the closure takes arguments of type `(i32,)`, but the required trait
+    //~| ERROR type mismatch
+}
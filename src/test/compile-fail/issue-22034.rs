@@ -14,7 +14,6 @@ fn main() {
     let ptr: *mut () = 0 as *mut _;
     let _: &mut Fn() = unsafe {
         &mut *(ptr as *mut Fn())
-        //~^ ERROR the trait `core::ops::Fn<()>` is not implemented
-        //~| ERROR the trait `core::ops::FnOnce<()>` is not implemented
+        //~^ ERROR does not satisfy the following bounds: `core::ops::Fn<()>`, `core::ops::FnOnce<()>`
     };
 }
@@ -0,0 +1,24 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that `Item` mismatches of `Iterator` and `IntoIterator` are
+// described in terms of what the iterator yields.
+
+fn want_u32s<I: Iterator<Item=u32>>(_: I) {}
+
+fn want_u32_source<I: IntoIterator<Item=u32>>(_: I) {}
+
+fn main() {
+    want_u32s(vec![1i64].into_iter());
+    //~^ ERROR yields `i64`, but `u32` was expected here
+
+    want_u32_source(vec![1i64]);
+    //~^ ERROR iterating over `collections::vec::Vec<i64>` yields `i64`, but `u32` was expected here
+}
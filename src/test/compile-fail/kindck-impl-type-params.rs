@@ -8,6 +8,8 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+// ignore-tidy-linelength
+
 // Issue #14061: tests the interaction between generic implementation
 // parameter bounds and trait objects.
 
@@ -26,15 +28,13 @@ impl<T: Send + Copy + 'static> Gettable<T> for S<T> {}
 fn f<T>(val: T) {
     let t: S<T> = S(marker::PhantomData);
     let a = &t as &Gettable<T>;
-    //~^ ERROR the trait `core::marker::Send` is not implemented
-    //~^^ ERROR the trait `core::marker::Copy` is not implemented
+    //~^ ERROR the type `T` does not satisfy the following bounds: `core::marker::Send`, `core::marker::Copy`
 }
 
 fn g<T>(val: T) {
     let t: S<T> = S(marker::PhantomData);
     let a: &Gettable<T> = &t;
-    //~^ ERROR the trait `core::marker::Send` is not implemented
-    //~^^ ERROR the trait `core::marker::Copy` is not implemented
+    //~^ ERROR the type `T` does not satisfy the following bounds: `core::marker::Send`, `core::marker::Copy`
 }
 
 fn foo<'a>() {
@@ -0,0 +1,32 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+
+trait A { fn foo(&self); }
+trait B { fn foo(&self); }
+trait Sub: A + B {}
+
+fn foo<T: Sub>(t: T) {
+    t.foo(); //~ ERROR E0034
+    //~^ HELP `A` and `B` are both supertraits of `Sub` and each defines `foo`; use fully-qualified syntax to pick one: `<T as A>::foo(...)` or `<T as B>::foo(...)`
+}
+
+trait C { fn bar(&self); }
+trait D { fn bar(&self); }
+trait E { fn bar(&self); }
+trait Deep: C + D + E {}
+
+fn bar<T: Deep>(t: T) {
+    t.bar(); //~ ERROR E0034
+    //~^ HELP `C`, `D` and `E` are all supertraits of `Deep` and each defines `bar`; use fully-qualified syntax to pick one: `<T as C>::bar(...)`, `<T as D>::bar(...)` or `<T as E>::bar(...)`
+}
+
+fn main() {}
@@ -0,0 +1,34 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+
+// Several traits missing on the same type at the same place are
+// reported as one error, and the cause is explained once.
+
+use std::rc::Rc;
+
+fn share<T: Send + Sync>(_: T) {}
+
+fn clone_both<A: Clone, B: Clone>(_: A, _: B) {}
+
+struct NotClone;
+struct AlsoNotClone;
+
+fn main() {
+    share(Rc::new(0u8));
+    //~^ ERROR the type `alloc::rc::Rc<u8>` does not satisfy the following bounds: `core::marker::Send`, `core::marker::Sync`
+    //~| NOTE required by `share`
+
+    // Different types are not grouped.
+    clone_both(NotClone, AlsoNotClone);
+    //~^ ERROR the trait `core::clone::Clone` is not implemented for the type `NotClone`
+    //~| ERROR the trait `core::clone::Clone` is not implemented for the type `AlsoNotClone`
+}
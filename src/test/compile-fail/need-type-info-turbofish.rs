@@ -0,0 +1,19 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Test that an unresolved type parameter of a call is pointed out with
+// a turbofish template.
+
+fn main() {
+    let x = Vec::new();
+    //~^ ERROR unable to infer enough type information about `_`
+    //~| HELP consider giving the type parameters explicitly, as in `Vec::<_>::new()`
+}
@@ -0,0 +1,33 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// ignore-tidy-linelength
+
+// Test that the object type alias built from a trait's type parameter
+// defaults is only suggested when every default can be written in an
+// alias. A default of `Self` cannot, so `SelfDefault` gets no alias.
+
+trait ConcreteDefault<T=u8> {
+    fn combine<U>(&self, u: U);
+}
+
+trait SelfDefault<Rhs=Self> {
+    fn combine<U>(&self, u: U);
+}
+
+fn concrete(x: &ConcreteDefault<u8>) {}
+//~^ ERROR the trait `ConcreteDefault` cannot be made into an object
+//~| NOTE the type parameter defaults give a usable object type: `type ConcreteDefaultObject = ConcreteDefault<u8>;`
+
+fn self_default(x: &SelfDefault<u8>) {}
+//~^ ERROR the trait `SelfDefault` cannot be made into an object
+
+fn main() {}
@@ -0,0 +1,26 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Once `Bad` is reported as not object-safe, the trait requirements on
+// the object type `Bad` at the same place, which cannot hold for that
+// reason, are not reported as well.
+
+trait Bad {
+    fn generic<T>(&self, t: T);
+}
+
+fn takes<T: Bad + ?Sized>() {}
+
+fn main() {
+    takes::<Bad>();
+    //~^ ERROR E0038
+    //~| NOTE method `generic` has generic type parameters
+    //~| ERROR E0038
+}
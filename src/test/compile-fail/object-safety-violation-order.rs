@@ -0,0 +1,29 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the object-safety violations of a trait are listed in a
+// fixed order: methods by name, whatever order they are declared in.
+
+trait Bar {
+    fn zeta(&self) -> Self;
+    fn alpha();
+    fn mu<T>(&self, t: T);
+}
+
+fn make_bar<T:Bar>(t: &T) -> &Bar {
+    t
+        //~^ ERROR E0038
+        //~| NOTE method `alpha` has no receiver
+        //~| NOTE method `mu` has generic type parameters
+        //~| NOTE method `zeta` references the `Self` type in its arguments or return type
+}
+
+fn main() {
+}
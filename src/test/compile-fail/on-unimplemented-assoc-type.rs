@@ -0,0 +1,29 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Associated types of the trait can be substituted into the message;
+// those that cannot be determined are rendered as `_`.
+
+#![feature(on_unimplemented)]
+
+#[rustc_on_unimplemented = "`{Self}` is not a stream (its `{Item}` could not be determined)"]
+trait Stream {
+    type Item;
+}
+
+struct Empty;
+
+fn drain<S: Stream>(_: S) {}
+
+fn main() {
+    drain(Empty);
+    //~^ ERROR the trait `Stream` is not implemented for the type `Empty`
+    //~| NOTE `Empty` is not a stream (its `_` could not be determined)
+}
@@ -37,5 +37,47 @@ trait BadAnnotation2<A,B>
 trait BadAnnotation3<A,B>
 {}
 
+#[rustc_on_unimplemented = "a note"]
+#[rustc_on_unimplemented(label = "a label")]
+//~^ ERROR forms cannot be combined
+trait BadAnnotation4
+{}
+
+#[rustc_on_unimplemented(label)]
+//~^ ERROR each item of this attribute must be `message`, `note`, `help` or `label`
+trait BadAnnotation5
+{}
+
+#[rustc_on_unimplemented(label = "`{Self}` is not `{A}`")]
+//~^ ERROR there is no type parameter A on trait BadAnnotation6
+trait BadAnnotation6
+{}
+
+#[rustc_on_unimplemented(hint = "a hint")]
+//~^ ERROR each item of this attribute must be `message`, `note`, `help` or `label`
+trait BadAnnotation7
+{}
+
+#[rustc_on_unimplemented = "`{Self}` is not `{0}`"]
+//~^ ERROR positional substitution parameter `{0}` is not allowed
+trait BadAnnotation8<A>
+{}
+
+#[rustc_on_unimplemented = "`{Self}` is not a } block"]
+//~^ ERROR invalid format string: unmatched `}` found
+trait BadAnnotation9
+{}
+
+#[rustc_on_unimplemented(on(A = "u8"), note = "not with a byte")]
+//~^ ERROR there is no type parameter A on trait BadAnnotation10
+trait BadAnnotation10
+{}
+
+#[rustc_on_unimplemented = "`{Self}` cannot be combined with `{Tpe}`"]
+//~^ ERROR there is no type parameter Tpe on trait BadAnnotation11
+//~| HELP did you mean `{Type}`?
+trait BadAnnotation11<Type>
+{}
+
 pub fn main() {
 }
@@ -0,0 +1,38 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Test that `on(...)` guards select a `#[rustc_on_unimplemented]`
+// attribute by the types involved, falling back to unguarded ones, and
+// that a `message` replaces the error's own.
+
+#![feature(on_unimplemented)]
+
+#[rustc_on_unimplemented(on(Self = "{integer}"),
+                         note = "integers are not shapes; try `Square({Self})`")]
+#[rustc_on_unimplemented(on(Self = "&'static str"),
+                         message = "`{Self}` names a shape but is not one",
+                         note = "names are not shapes; try `Shape::named(..)`")]
+#[rustc_on_unimplemented(note = "`{Self}` is not a shape")]
+trait Shape {}
+
+fn area<S: Shape>(_: S) {}
+
+fn main() {
+    area(3);
+    //~^ ERROR the trait `Shape` is not implemented
+    //~| NOTE integers are not shapes
+    area("circle");
+    //~^ ERROR `&'static str` names a shape but is not one
+    //~| NOTE names are not shapes
+    area(1.5f64);
+    //~^ ERROR the trait `Shape` is not implemented
+    //~| NOTE `f64` is not a shape
+}
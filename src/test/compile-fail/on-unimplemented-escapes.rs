@@ -0,0 +1,26 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Test that `{{` and `}}` in a `#[rustc_on_unimplemented]` message are
+// printed as literal braces.
+
+#![feature(on_unimplemented)]
+
+#[rustc_on_unimplemented = "`{Self}` cannot be used in a `{{ ... }}` block"]
+trait Block {}
+
+fn run<T: Block>(_: T) {}
+
+fn main() {
+    run(0u32);
+    //~^ ERROR the trait `Block` is not implemented for the type `u32`
+    //~| NOTE `u32` cannot be used in a `{ ... }` block
+}
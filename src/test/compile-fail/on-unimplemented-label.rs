@@ -0,0 +1,28 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test the `label`, `note` and `help` keys of `#[rustc_on_unimplemented]`.
+
+#![feature(on_unimplemented)]
+
+#[rustc_on_unimplemented(label = "`{Self}` is not a widget",
+                         note = "widgets must implement `Widget<{T}>` to be drawn",
+                         help = "wrap the `{Self}` in a `Frame` to draw it")]
+trait Widget<T> {}
+
+fn draw<W: Widget<u8>>(_: W) {}
+
+fn main() {
+    draw(1i32);
+    //~^ ERROR the trait `Widget<u8>` is not implemented for the type `i32`
+    //~| NOTE `i32` is not a widget
+    //~| NOTE widgets must implement `Widget<u8>` to be drawn
+    //~| HELP wrap the `i32` in a `Frame` to draw it
+}
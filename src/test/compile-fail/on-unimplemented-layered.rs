@@ -0,0 +1,30 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Test that every `#[rustc_on_unimplemented]` attribute on a trait
+// contributes its messages.
+
+#![feature(on_unimplemented)]
+
+#[rustc_on_unimplemented(note = "`{Self}` cannot be sent to a worker")]
+#[rustc_on_unimplemented(note = "jobs are moved between threads",
+                         help = "wrap the state of `{Self}` in a `Mutex`")]
+trait Job {}
+
+fn spawn<J: Job>(_: J) {}
+
+fn main() {
+    spawn(1u8);
+    //~^ ERROR the trait `Job` is not implemented for the type `u8`
+    //~| NOTE `u8` cannot be sent to a worker
+    //~| NOTE jobs are moved between threads
+    //~| HELP wrap the state of `u8` in a `Mutex`
+}
@@ -0,0 +1,29 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// `#[rustc_on_unimplemented]` on anything but a trait is ignored, and
+// warned about.
+
+#![feature(on_unimplemented)]
+#![deny(unused_attributes)]
+
+#[rustc_on_unimplemented = "not a trait"]
+//~^ ERROR `#[rustc_on_unimplemented]` has no effect on anything but a trait
+fn helper() {}
+
+struct S;
+
+#[rustc_on_unimplemented = "not a trait either"]
+//~^ ERROR `#[rustc_on_unimplemented]` has no effect on anything but a trait
+impl S {}
+
+fn main() {
+    helper();
+}
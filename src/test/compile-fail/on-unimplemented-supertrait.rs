@@ -0,0 +1,27 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// A trait without a `#[rustc_on_unimplemented]` message of its own
+// inherits the message of its supertrait, attributed to it.
+
+#![feature(on_unimplemented)]
+
+#[rustc_on_unimplemented = "`{Self}` cannot be drawn"]
+trait Draw {}
+
+trait Widget: Draw {}
+
+fn show<W: Widget>(_: W) {}
+
+fn main() {
+    show(0u8);
+    //~^ ERROR the trait `Widget` is not implemented for the type `u8`
+    //~| NOTE `u8` cannot be drawn (from the supertrait `Draw`)
+}
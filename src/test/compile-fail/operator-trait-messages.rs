@@ -0,0 +1,53 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Test that an unimplemented operator trait is reported in terms of
+// the operator it desugars, and that other uses of the trait keep the
+// generic message.
+
+use std::ops::Add;
+
+#[derive(Default)]
+struct Foo;
+
+#[derive(Default)]
+struct Bar;
+
+#[derive(Default)]
+struct Baz;
+
+impl PartialEq<Foo> for Foo {
+    fn eq(&self, _: &Foo) -> bool { true }
+}
+
+impl PartialEq<Baz> for Foo {
+    fn eq(&self, _: &Baz) -> bool { true }
+}
+
+fn total<T: Add>(_: T) {}
+
+fn main() {
+    1 + "a";
+    //~^ ERROR cannot add `&str` to `i32`
+
+    // With no impl of the trait for the left-hand side at all, the
+    // operator is rejected during type checking instead.
+    "a" + 1;
+    //~^ ERROR binary operation `+` cannot be applied to type `&'static str`
+
+    let other = Default::default();
+    Foo == other;
+    //~^ ERROR cannot compare `Foo` with `Bar`
+    let _: Bar = other;
+
+    total(Foo);
+    //~^ ERROR the trait `core::ops::Add` is not implemented for the type `Foo`
+}
@@ -0,0 +1,35 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z verbose
+
+// Test that `-Z verbose` explains, for a mismatched projection, why
+// each impl that could define the associated type was not used.
+
+trait Convert {
+    type Out;
+}
+
+struct Wrapper<T>(T);
+
+impl Convert for Wrapper<u32> {
+    type Out = u32;
+}
+
+impl<T: Copy> Convert for Wrapper<Vec<T>> {
+    type Out = T;
+}
+
+fn convert<C: Convert<Out=u8>>(_: C) {}
+
+fn main() {
+    convert(Wrapper(0u32));
+    //~^ ERROR type mismatch resolving
+}
@@ -0,0 +1,21 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// A projection mismatch required by a call points at the argument the
+// projection's input type came from.
+
+fn sum_bytes<I: Iterator<Item=u8>>(_: I) {}
+
+fn main() {
+    let wide = vec![1u16, 2, 3];
+    sum_bytes(wide.into_iter());
+    //~^ ERROR type mismatch resolving
+    //~| NOTE this expression has type `collections::vec::IntoIter<u16>`
+}
@@ -0,0 +1,31 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that a projection mismatch names the associated type that
+// diverged, along with the required and actual types.
+
+trait Shape {
+    type Area;
+}
+
+struct Square;
+
+impl Shape for Square {
+    type Area = u32;
+}
+
+fn area<S: Shape<Area=i32>>(_: S) {}
+
+fn main() {
+    area(Square);
+    //~^ ERROR expected `<Square as Shape>::Area` to be `i32`, found `u32`
+    //~| NOTE expected type `i32`
+    //~| NOTE found type `u32`
+}
@@ -8,6 +8,8 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+// ignore-tidy-linelength
+
 // Test range syntax - type errors.
 
 pub fn main() {
@@ -17,9 +19,8 @@ pub fn main() {
 
     // Bool => does not implement iterator.
     for i in false..true {}
-    //~^ ERROR the trait
-    //~^^ ERROR the trait
-    //~^^^ ERROR the trait
+    //~^ ERROR the type `bool` does not satisfy the following bounds: `core::iter::Step`, `core::num::One`
+    //~| ERROR core::ops::Add
 
     // Unsized type.
     let arr: &[_] = &[1, 2, 3];
@@ -0,0 +1,31 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z related-trait-errors
+
+// Test that errors derived from the same root obligation point at each
+// other.
+
+trait Tr {}
+
+struct Foo<T>(T);
+
+impl<T: Copy> Tr for Foo<T> {}
+
+fn need<T: Tr>(_: T) {}
+
+fn main() {
+    need(Foo(Box::new(0u8)));
+    //~^ ERROR the trait `core::marker::Copy` is not implemented for the type `Box<u8>`
+    //~| NOTE see the related error at
+    need(Foo(Box::new(0u8)));
+    //~^ ERROR the trait `core::marker::Copy` is not implemented for the type `Box<u8>`
+    //~| NOTE see the related error at
+}
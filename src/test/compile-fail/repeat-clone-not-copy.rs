@@ -0,0 +1,23 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that `[x; N]` with a `Clone` but not `Copy` element suggests
+// constructions that clone it, and that no such help is given when
+// the element isn't `Clone` either.
+
+struct NotClone;
+
+fn main() {
+    let _ = [String::new(); 4];
+    //~^ ERROR the trait `core::marker::Copy` is not implemented
+    //~| HELP consider `vec![x; N]`, which clones it
+    let _ = [NotClone; 4];
+    //~^ ERROR the trait `core::marker::Copy` is not implemented
+}
@@ -0,0 +1,46 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that `#[allow(rfc_1214)]` silences RFC1214 warnings within the
+// item or module it is attached to, and only there.
+
+#![allow(dead_code)]
+#![allow(unused_variables)]
+
+struct WantEq<T:Eq> { t: T }
+
+struct NotEq;
+
+trait Trait<T> { }
+
+#[allow(rfc_1214)]
+mod allowed {
+    use super::{Trait, WantEq, NotEq};
+
+    fn foo() {
+        let x: Box<Trait<WantEq<NotEq>>> = loop { };
+    }
+}
+
+#[allow(rfc_1214)]
+fn foo() {
+    let x: Box<Trait<WantEq<NotEq>>> = loop { };
+}
+
+fn bar() {
+    let x: Box<Trait<WantEq<NotEq>>> = loop { };
+    //~^ WARN E0277
+    wf::<WantEq<NotEq>>();
+    //~^ ERROR E0277
+}
+
+fn wf<T>() { }
+
+fn main() { }
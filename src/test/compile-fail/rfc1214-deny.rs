@@ -0,0 +1,34 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that `#[deny(rfc_1214)]` turns RFC1214 warnings into errors
+// within the item it is attached to, and only there.
+
+#![allow(dead_code)]
+#![allow(unused_variables)]
+
+struct WantEq<T:Eq> { t: T }
+
+struct NotEq;
+
+trait Trait<T> { }
+
+#[deny(rfc_1214)]
+fn foo() {
+    let x: Box<Trait<WantEq<NotEq>>> = loop { };
+    //~^ ERROR E0277
+}
+
+fn bar() {
+    let x: Box<Trait<WantEq<NotEq>>> = loop { };
+    //~^ WARN E0277
+}
+
+fn main() { }
@@ -0,0 +1,23 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that a missing supertrait impl is attributed to the supertrait
+// relationship rather than reported as a plain requirement.
+
+trait Super {}
+trait Sub: Super {}
+
+struct S;
+
+impl Sub for S {}
+//~^ ERROR the trait `Super` is not implemented for the type `S`
+//~| NOTE required because `Sub` has supertrait `Super`
+
+fn main() {}
@@ -0,0 +1,49 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+
+// Test that a bound which would hold for a reference to the value, or for
+// the value behind a reference, suggests borrowing or dereferencing.
+
+use std::slice;
+
+struct Bag(Vec<i32>);
+
+impl<'a> IntoIterator for &'a Bag {
+    type Item = &'a i32;
+    type IntoIter = slice::Iter<'a, i32>;
+    fn into_iter(self) -> slice::Iter<'a, i32> { self.0.iter() }
+}
+
+fn count<I: IntoIterator>(items: I) -> usize {
+    items.into_iter().count()
+}
+
+fn default_like<T: Default>(_: T) {}
+
+fn main() {
+    let bag = Bag(vec![1, 2, 3]);
+    count(bag);
+    //~^ ERROR the trait `core::iter::Iterator` is not implemented for the type `Bag`
+    //~| HELP the trait `core::iter::IntoIterator` is implemented for `&Bag`; consider borrowing here
+
+    for _ in Bag(vec![]) {}
+    //~^ ERROR the trait `core::iter::Iterator` is not implemented for the type `Bag`
+    //~| HELP the trait `core::iter::IntoIterator` is implemented for `&Bag`; consider borrowing here
+
+    default_like(&1i32);
+    //~^ ERROR the trait `core::default::Default` is not implemented for the type `&i32`
+    //~| HELP the trait `core::default::Default` is implemented for `i32`; consider dereferencing here
+
+    count(vec![1, 2, 3]);
+    count(5u8);
+    //~^ ERROR the trait `core::iter::Iterator` is not implemented for the type `u8`
+}
@@ -0,0 +1,28 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Tests that when an impl method has several bounds the trait method
+// lacks, every one of them is listed in a note.
+
+trait A {
+    fn b<C, D>(&self, x: C) -> C;
+}
+
+struct E;
+
+impl A for E {
+    fn b<F: Sync, G: Send>(&self, _x: F) -> F { panic!() }
+    //~^ ERROR `F : core::marker::Sync` appears on the impl method
+    //~| NOTE the impl method has these bounds that the trait method does not
+    //~| ERROR `G : core::marker::Send` appears on the impl method
+    //~| NOTE the impl method has these bounds that the trait method does not
+}
+
+fn main() {}
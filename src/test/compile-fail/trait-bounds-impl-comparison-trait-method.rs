@@ -0,0 +1,26 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Tests that an impl method with a bound the trait method lacks points
+// at the trait method and suggests where the bound would go.
+
+trait Store {
+    fn put<T>(&mut self, value: T); //~ NOTE trait method declared here
+}
+
+struct Shelf;
+
+impl Store for Shelf {
+    fn put<T: Send>(&mut self, _value: T) {}
+    //~^ ERROR `T : core::marker::Send` appears on the impl method
+    //~| HELP consider adding `where T : core::marker::Send` to the trait method `Store::put`
+}
+
+fn main() {}
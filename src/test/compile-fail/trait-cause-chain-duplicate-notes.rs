@@ -0,0 +1,30 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// A cause chain that passes through `Node` twice, once for `Sync` and
+// once for `Send`, explains that `Rc<u8>` is within `Node` only once,
+// but still names every other type it passes through.
+
+use std::rc::Rc;
+
+struct Node {
+    next: Option<&'static Node>,
+    data: Rc<u8>,
+}
+
+fn is_send<T: Send>() {}
+
+fn main() {
+    is_send::<Node>();
+    //~^ ERROR the trait `core::marker::Send` is not implemented for the type `alloc::rc::Rc<u8>`
+    //~| ERROR the trait `core::marker::Sync` is not implemented for the type `alloc::rc::Rc<u8>`
+    //~| NOTE required because it appears within the type `Node`
+    //~| NOTE required because it appears within the type `core::option::Option<&'static Node>`
+}
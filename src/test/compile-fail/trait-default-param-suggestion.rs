@@ -0,0 +1,29 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that a bound failing only because a type parameter of the trait
+// was left at its default suggests giving the parameter explicitly.
+
+use std::ops::Add;
+
+struct Meters(u32);
+
+impl Add<u32> for Meters {
+    type Output = Meters;
+    fn add(self, other: u32) -> Meters { Meters(self.0 + other) }
+}
+
+fn total<T: Add>(_: T) {}
+
+fn main() {
+    total(Meters(1));
+    //~^ ERROR the trait `core::ops::Add` is not implemented for the type `Meters`
+    //~| HELP `Meters` does implement `core::ops::Add<u32>`
+}
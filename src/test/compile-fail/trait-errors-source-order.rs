@@ -0,0 +1,30 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Trait errors from one body are reported top to bottom, whatever the
+// order in which the obligations were processed.
+
+use std::rc::Rc;
+
+fn needs_clone<T: Clone>(_: T) {}
+fn needs_send<T: Send>(_: T) {}
+fn needs_copy<T: Copy>(_: T) {}
+
+struct NotClone;
+
+fn main() {
+    let shared = Rc::new(0u8);
+    needs_clone(NotClone);
+    //~^ ERROR the trait `core::clone::Clone` is not implemented for the type `NotClone`
+    needs_send(shared);
+    //~^ ERROR the trait `core::marker::Send` is not implemented for the type `alloc::rc::Rc<u8>`
+    needs_copy(String::new());
+    //~^ ERROR the trait `core::marker::Copy` is not implemented for the type `collections::string
+}
@@ -0,0 +1,35 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that an unimplemented trait lists the impls for types of the
+// same sort as the one wanted, at most four of them.
+
+trait Foo {}
+
+impl Foo for u8 {}
+impl Foo for i8 {}
+impl Foo for u16 {}
+impl Foo for i16 {}
+impl Foo for u64 {}
+impl Foo for i64 {}
+impl Foo for bool {}
+
+fn want<T: Foo>(_: T) {}
+
+fn main() {
+    want(5u32);
+    //~^ ERROR the trait `Foo` is not implemented for the type `u32`
+    //~| NOTE the following implementations were found
+    //~| NOTE <i16 as Foo>
+    //~| NOTE <i64 as Foo>
+    //~| NOTE <i8 as Foo>
+    //~| NOTE <u16 as Foo>
+    //~| NOTE and 2 others
+}
@@ -0,0 +1,25 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Test that an unimplemented trait on a type parameter of the enclosing
+// fn suggests requiring it.
+
+fn dup<T: Clone>(x: &T) -> (T, T) {
+    (x.clone(), x.clone())
+}
+
+fn twice<T>(x: &T) -> (T, T) {
+    dup(x)
+    //~^ ERROR the trait `core::clone::Clone` is not implemented for the type `T`
+    //~| HELP consider adding a `where T: core::clone::Clone` bound
+}
+
+fn main() {}